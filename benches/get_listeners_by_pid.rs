@@ -0,0 +1,51 @@
+use crate::helpers::{SystemLoad, cleanup, save_chart_svg, save_info_txt};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use rand::prelude::IndexedRandom;
+use std::hint::black_box;
+
+#[path = "helpers.rs"]
+mod helpers;
+
+fn benchmark_get_listeners_by_pid_low(c: &mut Criterion) {
+    benchmark_get_listeners_by_pid(c, SystemLoad::Low);
+}
+
+fn benchmark_get_listeners_by_pid_medium(c: &mut Criterion) {
+    benchmark_get_listeners_by_pid(c, SystemLoad::Medium);
+}
+
+fn benchmark_get_listeners_by_pid_high(c: &mut Criterion) {
+    benchmark_get_listeners_by_pid(c, SystemLoad::High);
+}
+
+fn benchmark_get_listeners_by_pid(c: &mut Criterion, system_load: SystemLoad) {
+    let id = format!("get_listeners_by_pid_{system_load}");
+
+    // prepare bench
+    let (sockets, bench_info) = system_load.activate();
+
+    let pids: Vec<u32> = sockets.iter().map(std::process::Child::id).collect();
+    let mut rng = rand::rng();
+    c.bench_function(&id, |b| {
+        b.iter_batched(
+            || *pids.choose(&mut rng).unwrap(),
+            |pid| black_box(listeners::get_listeners_by_pid(black_box(pid)).unwrap()),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // save files
+    save_chart_svg(&id, &bench_info);
+    save_info_txt(&id, &bench_info);
+
+    // cleanup bench
+    cleanup(sockets);
+}
+
+criterion_group!(
+    benches,
+    benchmark_get_listeners_by_pid_low,
+    benchmark_get_listeners_by_pid_medium,
+    benchmark_get_listeners_by_pid_high
+);
+criterion_main!(benches);
@@ -57,7 +57,7 @@ impl BenchInfo {
         // to test the "not found" case: use random ports/protocols that aren't in the list
         let mut rng = rand::rng();
         let all_ports: Vec<u16> = (1..u16::MAX).collect();
-        let all_protocols = vec![Protocol::TCP, Protocol::UDP];
+        let all_protocols = [Protocol::TCP, Protocol::UDP];
         let mut inactive_ports_protos = Vec::new();
         while inactive_ports_protos.len() < 1_000 {
             let port = *all_ports.choose(&mut rng).unwrap();
@@ -237,5 +237,5 @@ pub fn save_info_txt(benchmark_id: &str, bench_info: &BenchInfo) {
     let mean_ns = json["mean"]["point_estimate"].as_f64().unwrap();
     let mean_ms = (mean_ns / 1_000_000.0).round() as usize;
     let dest = format!("resources/benchmarks/{OS}_{benchmark_id}.txt");
-    std::fs::write(&dest, &format!("{bench_info}\n\n{mean_ms} ms")).unwrap();
+    std::fs::write(&dest, format!("{bench_info}\n\n{mean_ms} ms")).unwrap();
 }
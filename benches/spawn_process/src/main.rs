@@ -6,9 +6,11 @@ use std::time::Duration;
 fn main() {
     // read the first CLI argument as the number of sockets to spawn
     let n_string = std::env::args().nth(1).unwrap();
+    // read the optional second CLI argument as the port of the first TCP socket
+    let port: u16 = std::env::args().nth(2).map_or(0, |p| p.parse().unwrap());
 
     // spawn n sockets
-    let _sockets = spawn_sockets(&n_string);
+    let _sockets = spawn_sockets(&n_string, port);
 
     // keep the process alive to allow the benchmark to run
     loop {
@@ -22,7 +24,7 @@ enum SocketType {
     Udp(UdpSocket),
 }
 
-fn spawn_sockets(n_string: &str) -> Vec<SocketType> {
+fn spawn_sockets(n_string: &str, port: u16) -> Vec<SocketType> {
     let n: f32 = n_string.parse().unwrap();
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     let n_4_up = (n / 4.0).ceil() as usize;
@@ -33,7 +35,12 @@ fn spawn_sockets(n_string: &str) -> Vec<SocketType> {
     let socket_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
     let socket_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0);
 
-    for _ in 0..n_4_up {
+    for i in 0..n_4_up {
+        let socket_v4 = if i == 0 {
+            SocketAddr::new(socket_v4.ip(), port)
+        } else {
+            socket_v4
+        };
         let socket = TcpListener::bind(socket_v4).unwrap();
         sockets.push(SocketType::Tcp(socket));
     }
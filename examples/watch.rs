@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use listeners::{ListenerEvent, SocketState};
+
+#[allow(clippy::needless_doctest_main)]
+fn main() {
+    // Get notified when TCP ports are taken or freed
+    if let Ok(watcher) = listeners::watch(Duration::from_secs(1)) {
+        for event in watcher.flatten() {
+            match event {
+                ListenerEvent::Opened(l) if l.state == SocketState::Listen => {
+                    let port = l.socket.port();
+                    let p = l.process;
+                    println!("Port {port} is now taken by {} (PID {})", p.name, p.pid);
+                }
+                ListenerEvent::Closed(l) if l.state == SocketState::Listen => {
+                    println!("Port {} is now free", l.socket.port());
+                }
+                _ => {}
+            }
+        }
+    }
+}
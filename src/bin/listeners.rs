@@ -0,0 +1,278 @@
+//! Command-line tool to find out processes listening on network ports.
+//!
+//! Available with the `cli` feature.
+
+use std::fmt::Write;
+use std::io::Write as _;
+use std::process::ExitCode;
+
+use listeners::{Filter, Listener, ListenerQuery, Protocol};
+
+const USAGE: &str = "\
+Find out processes listening on network ports
+
+Usage: listeners [OPTIONS]
+
+Options:
+  -p, --port <PORT>      Only show sockets bound to the given local port, can be repeated
+      --pid <PID>        Only show sockets owned by the given process ID, can be repeated
+  -n, --name <NAME>      Only show sockets owned by processes with the given name, executable path, or glob pattern, can be repeated
+  -t, --tcp              Only show TCP sockets
+  -u, --udp              Only show UDP sockets
+  -s, --state <STATE>    Only show sockets in the given state (e.g. LISTEN), can be repeated
+  -f, --filter <EXPR>    Only show sockets matching the given filter expression
+                         (e.g. \"tcp and state listen and sport >= 8000 and not src 127.0.0.1\")
+  -o, --output <FORMAT>  Output format: table, json, or csv [default: table]
+  -h, --help             Print help
+  -V, --version          Print version";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Args {
+    query: ListenerQuery,
+    // applied to the listeners retrieved by the query
+    filter: Option<Filter>,
+    format: Format,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    List(Box<Args>),
+    Help,
+    Version,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::List(args)) => *args,
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("listeners {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let matched = match args.query.execute() {
+        Ok(matched) => matched,
+        Err(e) => {
+            eprintln!("error: failed to retrieve listeners: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut listeners: Vec<Listener> = matched
+        .into_iter()
+        .filter(|l| args.filter.as_ref().is_none_or(|filter| filter.matches(l)))
+        .collect();
+    listeners.sort_by_key(|l| (l.socket.port(), l.process.pid, l.socket, l.remote));
+
+    let output = match args.format {
+        Format::Table => to_table(&listeners),
+        Format::Json => match serde_json::to_string_pretty(&listeners) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("error: failed to serialize listeners: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Format::Csv => to_csv(&listeners),
+    };
+    // a closed stdout (e.g. piping into `head`) isn't an error
+    let _ = writeln!(std::io::stdout().lock(), "{output}");
+
+    ExitCode::SUCCESS
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut parsed = Args::default();
+    let mut query = ListenerQuery::new();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for '{name}'"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-p" | "--port" => {
+                let v = value(&arg)?;
+                query = query.port(v.parse().map_err(|_| format!("invalid port '{v}'"))?);
+            }
+            "--pid" => {
+                let v = value(&arg)?;
+                query = query.pid(v.parse().map_err(|_| format!("invalid PID '{v}'"))?);
+            }
+            "-n" | "--name" => query = query.process(&value(&arg)?),
+            "-t" | "--tcp" => query = query.protocol(Protocol::TCP),
+            "-u" | "--udp" => query = query.protocol(Protocol::UDP),
+            "-s" | "--state" => {
+                let v = value(&arg)?;
+                query = query.state(v.parse().map_err(|_| format!("invalid state '{v}'"))?);
+            }
+            "-f" | "--filter" => {
+                let v = value(&arg)?;
+                parsed.filter = Some(v.parse().map_err(|e| format!("invalid filter: {e}"))?);
+            }
+            "-o" | "--output" => {
+                let v = value(&arg)?;
+                parsed.format = match v.to_ascii_lowercase().as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    _ => return Err(format!("invalid output format '{v}'")),
+                };
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    parsed.query = query;
+    Ok(Command::List(Box::new(parsed)))
+}
+
+fn to_table(listeners: &[Listener]) -> String {
+    let header = [
+        "PID", "PROCESS", "PROTO", "LOCAL", "REMOTE", "STATE", "PATH",
+    ];
+    let rows: Vec<[String; 7]> = listeners
+        .iter()
+        .map(|l| {
+            [
+                l.process.pid.to_string(),
+                l.process.name.clone(),
+                l.protocol.to_string(),
+                l.socket.to_string(),
+                l.remote.map(|r| r.to_string()).unwrap_or_default(),
+                l.state.to_string(),
+                l.process.path.clone(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    let header = header.map(String::from);
+    for row in std::iter::once(&header).chain(&rows) {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(widths) {
+            let _ = write!(line, "{cell:<width$}  ");
+        }
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table.pop();
+    table
+}
+
+fn to_csv(listeners: &[Listener]) -> String {
+    let mut csv = String::from("pid,name,path,protocol,socket,remote,state");
+    for l in listeners {
+        let _ = write!(
+            csv,
+            "\n{},{},{},{},{},{},{}",
+            l.process.pid,
+            csv_field(&l.process.name),
+            csv_field(&l.process.path),
+            l.protocol,
+            l.socket,
+            l.remote.map(|r| r.to_string()).unwrap_or_default(),
+            l.state
+        );
+    }
+    csv
+}
+
+/// Quotes a CSV field if it contains separators, quotes, or line breaks.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use listeners::{Filter, ListenerQuery, Protocol, SocketState};
+
+    use crate::{Args, Command, Format, csv_field, parse_args};
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse(&[]), Ok(Command::List(Box::default())));
+        assert_eq!(
+            parse(&[
+                "-p",
+                "8080",
+                "--port",
+                "443",
+                "--pid",
+                "42",
+                "-n",
+                "nginx",
+                "-t",
+                "-s",
+                "listen",
+                "--state",
+                "TIME_WAIT",
+                "-f",
+                "sport >= 8000",
+                "-o",
+                "csv"
+            ]),
+            Ok(Command::List(Box::new(Args {
+                query: ListenerQuery::new()
+                    .port(8080)
+                    .port(443)
+                    .pid(42)
+                    .process("nginx")
+                    .protocol(Protocol::TCP)
+                    .state(SocketState::Listen)
+                    .state(SocketState::TimeWait),
+                filter: Some(Filter::LocalPort(8000..=65535)),
+                format: Format::Csv,
+            })))
+        );
+        assert_eq!(parse(&["-u", "--help"]), Ok(Command::Help));
+        assert_eq!(parse(&["-V"]), Ok(Command::Version));
+
+        assert!(parse(&["--port"]).is_err());
+        assert!(parse(&["--port", "65536"]).is_err());
+        assert!(parse(&["--state", "LISTENING"]).is_err());
+        assert!(parse(&["--output", "xml"]).is_err());
+        assert!(parse(&["--filter", "tcp and"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("nginx"), "nginx");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,473 @@
+use std::fmt::Display;
+use std::net::{IpAddr, SocketAddr, TcpListener, ToSocketAddrs, UdpSocket};
+
+use crate::{Listener, ListenerQuery, Process, Protocol, SocketState, platform};
+
+/// Socket options of a prospective bind, affecting which existing sockets it conflicts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BindOptions {
+    /// Whether `SO_REUSEADDR` is set: TCP binds then only conflict with sockets in `LISTEN` state.
+    pub reuse_addr: bool,
+    /// Whether `IPV6_V6ONLY` is set: binding `[::]` then doesn't conflict with IPv4 sockets.
+    pub v6_only: bool,
+}
+
+/// The outcome of [`check_bind`]: the existing sockets a bind would conflict with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BindCheck {
+    /// The conflicting sockets, with their owning processes, sorted by local socket and PID.
+    pub conflicts: Vec<Listener>,
+    /// The number of conflicting TCP sockets in `TIME_WAIT` state, which aren't owned by any process.
+    ///
+    /// These are only reported on Linux.
+    pub time_wait_conflicts: usize,
+}
+
+impl BindCheck {
+    /// Returns whether the bind would fail with `EADDRINUSE`.
+    pub fn would_fail(&self) -> bool {
+        !self.conflicts.is_empty() || self.time_wait_conflicts > 0
+    }
+}
+
+/// Checks whether binding a socket to the given address would fail because the address is already in use.
+///
+/// Equivalent to [`check_bind_with`] with the default [`BindOptions`] (neither `SO_REUSEADDR` nor `IPV6_V6ONLY`).
+///
+/// # Arguments
+///
+/// * `addr` - The address to bind.
+/// * `protocol` - The protocol of the socket to bind (TCP or UDP).
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+///
+/// # Example
+///
+/// ```
+/// use std::net::SocketAddr;
+///
+/// use listeners::Protocol;
+///
+/// let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
+/// if let Ok(check) = listeners::check_bind(addr, Protocol::TCP)
+///     && check.would_fail()
+/// {
+///     for l in check.conflicts {
+///         println!("{addr} is already in use by {}", l.process);
+///     }
+/// }
+/// ```
+pub fn check_bind(addr: SocketAddr, protocol: Protocol) -> crate::Result<BindCheck> {
+    check_bind_with(addr, protocol, BindOptions::default())
+}
+
+/// Checks whether binding a socket with the given options would fail because the address is already in use.
+///
+/// The kernel's rules are applied:
+/// - a wildcard address (`0.0.0.0` or `[::]`) overlaps with every specific address of the same family;
+/// - `[::]` also overlaps with IPv4 addresses, unless [`BindOptions::v6_only`] is set
+///   (existing `[::]` sockets are always assumed to be dual-stack);
+/// - without `SO_REUSEADDR`, every TCP socket bound to an overlapping address conflicts,
+///   including connections and sockets in `TIME_WAIT` state;
+///   with `SO_REUSEADDR`, only TCP sockets in `LISTEN` state conflict;
+/// - every UDP socket bound to an overlapping address conflicts
+///   (`SO_REUSEADDR` only helps if the existing socket set it too, which can't be known).
+///
+/// Binding port 0 never conflicts, since the operating system picks a free port.
+///
+/// # Arguments
+///
+/// * `addr` - The address to bind.
+/// * `protocol` - The protocol of the socket to bind (TCP or UDP).
+/// * `options` - The socket options of the bind.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+pub fn check_bind_with(
+    addr: SocketAddr,
+    protocol: Protocol,
+    options: BindOptions,
+) -> crate::Result<BindCheck> {
+    if addr.port() == 0 {
+        return Ok(BindCheck::default());
+    }
+
+    let query = ListenerQuery::new().protocol(protocol).port(addr.port());
+    let listeners = query.execute()?;
+    let unowned = platform::get_unowned_sockets(&query);
+    Ok(check(listeners.iter(), &unowned, addr, protocol, options))
+}
+
+/// The error of a bind that failed because the address is already in use,
+/// carrying the processes holding it.
+///
+/// Returned by [`bind_tcp`] and [`bind_udp`] wrapped in an [`std::io::Error`] of kind
+/// [`std::io::ErrorKind::AddrInUse`], and accessible via [`std::io::Error::get_ref`].
+#[derive(Debug)]
+pub struct AddrInUseError {
+    /// The address that couldn't be bound.
+    pub addr: SocketAddr,
+    /// The protocol of the socket that couldn't be bound.
+    pub protocol: Protocol,
+    /// The processes holding the address, sorted by PID.
+    pub processes: Vec<Process>,
+    /// The number of sockets in `TIME_WAIT` state holding the address, which aren't owned by any process.
+    pub time_wait: usize,
+    source: std::io::Error,
+}
+
+impl Display for AddrInUseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let AddrInUseError {
+            addr,
+            protocol,
+            processes,
+            time_wait,
+            source,
+        } = self;
+        write!(f, "{protocol} address {addr} is held by ")?;
+        if processes.is_empty() && *time_wait == 0 {
+            return write!(f, "an unknown process ({source})");
+        }
+        for (i, p) in processes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "PID {} ({} {})", p.pid, p.name, p.path)?;
+        }
+        if *time_wait > 0 {
+            if !processes.is_empty() {
+                write!(f, ", and ")?;
+            }
+            write!(f, "{time_wait} socket(s) in TIME_WAIT state")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AddrInUseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Creates a [`TcpListener`] bound to the given address, like [`TcpListener::bind`].
+///
+/// If the address is already in use, the returned error explains which processes are holding it
+/// (see [`AddrInUseError`]); other errors are returned unchanged.
+///
+/// # Arguments
+///
+/// * `addr` - The address to bind; when it resolves to multiple addresses, each is tried in turn.
+///
+/// # Errors
+///
+/// This function returns an error if the socket can't be bound to any of the addresses.
+///
+/// # Example
+///
+/// ```
+/// match listeners::bind_tcp("127.0.0.1:8080") {
+///     Ok(listener) => println!("Listening on {:?}", listener.local_addr()),
+///     // e.g. "TCP address 127.0.0.1:8080 is held by PID 4411 (node /usr/bin/node)"
+///     Err(e) => eprintln!("{e}"),
+/// }
+/// ```
+pub fn bind_tcp(addr: impl ToSocketAddrs) -> std::io::Result<TcpListener> {
+    // the standard library sets `SO_REUSEADDR` on Unix platforms
+    let options = BindOptions {
+        reuse_addr: cfg!(unix),
+        ..BindOptions::default()
+    };
+    bind_each(addr, Protocol::TCP, options, TcpListener::bind)
+}
+
+/// Creates a [`UdpSocket`] bound to the given address, like [`UdpSocket::bind`].
+///
+/// If the address is already in use, the returned error explains which processes are holding it
+/// (see [`AddrInUseError`]); other errors are returned unchanged.
+///
+/// # Arguments
+///
+/// * `addr` - The address to bind; when it resolves to multiple addresses, each is tried in turn.
+///
+/// # Errors
+///
+/// This function returns an error if the socket can't be bound to any of the addresses.
+pub fn bind_udp(addr: impl ToSocketAddrs) -> std::io::Result<UdpSocket> {
+    bind_each(addr, Protocol::UDP, BindOptions::default(), UdpSocket::bind)
+}
+
+fn bind_each<T>(
+    addr: impl ToSocketAddrs,
+    protocol: Protocol,
+    options: BindOptions,
+    bind: impl Fn(SocketAddr) -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    let mut last_error = None;
+    for addr in addr.to_socket_addrs()? {
+        match bind(addr) {
+            Ok(socket) => return Ok(socket),
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                last_error = Some(explain_addr_in_use(e, addr, protocol, options));
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "could not resolve to any addresses",
+        )
+    }))
+}
+
+fn explain_addr_in_use(
+    source: std::io::Error,
+    addr: SocketAddr,
+    protocol: Protocol,
+    options: BindOptions,
+) -> std::io::Error {
+    let check = check_bind_with(addr, protocol, options).unwrap_or_default();
+    let mut processes: Vec<Process> = check.conflicts.into_iter().map(|l| l.process).collect();
+    processes.sort_by_key(|p| p.pid);
+    processes.dedup();
+
+    std::io::Error::new(
+        std::io::ErrorKind::AddrInUse,
+        AddrInUseError {
+            addr,
+            protocol,
+            processes,
+            time_wait: check.time_wait_conflicts,
+            source,
+        },
+    )
+}
+
+fn check<'a>(
+    listeners: impl Iterator<Item = &'a Listener>,
+    unowned: &[(SocketAddr, SocketState)],
+    addr: SocketAddr,
+    protocol: Protocol,
+    options: BindOptions,
+) -> BindCheck {
+    let conflicts_with = |socket: &SocketAddr, state: SocketState| {
+        socket.port() == addr.port()
+            && overlaps(addr, options.v6_only, *socket)
+            && (protocol == Protocol::UDP || !options.reuse_addr || state == SocketState::Listen)
+    };
+
+    let mut conflicts: Vec<Listener> = listeners
+        .filter(|l| l.protocol == protocol && conflicts_with(&l.socket, l.state))
+        .cloned()
+        .collect();
+    conflicts.sort_by_key(|l| (l.socket, l.process.pid, l.remote));
+
+    let time_wait_conflicts = if protocol == Protocol::TCP {
+        unowned
+            .iter()
+            .filter(|(socket, state)| {
+                *state == SocketState::TimeWait && conflicts_with(socket, *state)
+            })
+            .count()
+    } else {
+        0
+    };
+
+    BindCheck {
+        conflicts,
+        time_wait_conflicts,
+    }
+}
+
+/// Whether binding `addr` overlaps with an existing socket bound to `existing`.
+fn overlaps(addr: SocketAddr, v6_only: bool, existing: SocketAddr) -> bool {
+    let (ip, existing_ip) = (addr.ip().to_canonical(), existing.ip().to_canonical());
+    ip == existing_ip
+        || match (ip, existing_ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+                ip.is_unspecified() || existing_ip.is_unspecified()
+            }
+            (IpAddr::V6(_), IpAddr::V4(_)) => ip.is_unspecified() && !v6_only,
+            (IpAddr::V4(_), IpAddr::V6(_)) => existing_ip.is_unspecified(),
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    use crate::bind::{AddrInUseError, BindCheck, BindOptions, check};
+    use crate::{Listener, Process, Protocol, SocketState};
+
+    fn conflicting_pids(
+        listeners: &[&Listener],
+        addr: &str,
+        protocol: Protocol,
+        options: BindOptions,
+    ) -> Vec<u32> {
+        check(
+            listeners.iter().copied(),
+            &[],
+            SocketAddr::from_str(addr).unwrap(),
+            protocol,
+            options,
+        )
+        .conflicts
+        .iter()
+        .map(|l| l.process.pid)
+        .collect()
+    }
+
+    #[test]
+    fn test_check_overlapping_addresses() {
+        let v4_any = Listener::fixture(1, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let v4_local = Listener::fixture(2, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+        let v6_any = Listener::fixture(3, "[::]:8080", Protocol::TCP, SocketState::Listen);
+        let v6_local = Listener::fixture(4, "[::1]:8080", Protocol::TCP, SocketState::Listen);
+        let other_port = Listener::fixture(5, "0.0.0.0:8081", Protocol::TCP, SocketState::Listen);
+        let udp = Listener::fixture(6, "0.0.0.0:8080", Protocol::UDP, SocketState::Unconnected);
+        let default = BindOptions::default();
+
+        assert_eq!(
+            conflicting_pids(
+                &[&v4_local, &other_port, &udp],
+                "127.0.0.1:8080",
+                Protocol::TCP,
+                default
+            ),
+            [2]
+        );
+        assert!(conflicting_pids(&[&v4_local], "10.0.0.5:8080", Protocol::TCP, default).is_empty());
+        assert_eq!(
+            conflicting_pids(&[&v4_any], "10.0.0.5:8080", Protocol::TCP, default),
+            [1]
+        );
+        assert_eq!(
+            conflicting_pids(
+                &[&v4_local, &v6_local],
+                "0.0.0.0:8080",
+                Protocol::TCP,
+                default
+            ),
+            [2]
+        );
+        // dual-stack sockets
+        assert_eq!(
+            conflicting_pids(&[&v6_any], "127.0.0.1:8080", Protocol::TCP, default),
+            [3]
+        );
+        assert_eq!(
+            conflicting_pids(&[&v4_local, &v6_local], "[::]:8080", Protocol::TCP, default),
+            [2, 4]
+        );
+        let v6_only = BindOptions {
+            v6_only: true,
+            ..default
+        };
+        assert_eq!(
+            conflicting_pids(&[&v4_local, &v6_local], "[::]:8080", Protocol::TCP, v6_only),
+            [4]
+        );
+        assert_eq!(
+            conflicting_pids(
+                &[&v4_local],
+                "[::ffff:127.0.0.1]:8080",
+                Protocol::TCP,
+                v6_only
+            ),
+            [2]
+        );
+        assert_eq!(
+            conflicting_pids(&[&udp], "127.0.0.1:8080", Protocol::UDP, default),
+            [6]
+        );
+    }
+
+    #[test]
+    fn test_check_reuse_addr() {
+        let listen = Listener::fixture(1, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let connection =
+            Listener::fixture(2, "127.0.0.1:8080", Protocol::TCP, SocketState::Established);
+        let udp = Listener::fixture(3, "127.0.0.1:8080", Protocol::UDP, SocketState::Unconnected);
+        let time_wait = [(
+            SocketAddr::from_str("127.0.0.1:8080").unwrap(),
+            SocketState::TimeWait,
+        )];
+        let addr = SocketAddr::from_str("127.0.0.1:8080").unwrap();
+        let reuse_addr = BindOptions {
+            reuse_addr: true,
+            ..BindOptions::default()
+        };
+
+        let without = check(
+            [&connection].into_iter(),
+            &time_wait,
+            addr,
+            Protocol::TCP,
+            BindOptions::default(),
+        );
+        assert_eq!(without.conflicts, std::slice::from_ref(&connection));
+        assert_eq!(without.time_wait_conflicts, 1);
+        assert!(without.would_fail());
+
+        let with = check(
+            [&connection].into_iter(),
+            &time_wait,
+            addr,
+            Protocol::TCP,
+            reuse_addr,
+        );
+        assert_eq!(with, BindCheck::default());
+        assert!(!with.would_fail());
+
+        assert_eq!(
+            conflicting_pids(
+                &[&listen, &connection],
+                "127.0.0.1:8080",
+                Protocol::TCP,
+                reuse_addr
+            ),
+            [1]
+        );
+        assert_eq!(
+            conflicting_pids(&[&udp], "127.0.0.1:8080", Protocol::UDP, reuse_addr),
+            [3]
+        );
+    }
+
+    #[test]
+    fn test_addr_in_use_error_to_string() {
+        let error = |processes: Vec<Process>, time_wait: usize| AddrInUseError {
+            addr: SocketAddr::from_str("127.0.0.1:8080").unwrap(),
+            protocol: Protocol::TCP,
+            processes,
+            time_wait,
+            source: std::io::ErrorKind::AddrInUse.into(),
+        };
+        let node = Process::new(4411, "node".to_string(), "/usr/bin/node".to_string());
+        let nginx = Process::new(80, "nginx".to_string(), "/usr/sbin/nginx".to_string());
+
+        assert_eq!(
+            error(vec![node.clone()], 0).to_string(),
+            "TCP address 127.0.0.1:8080 is held by PID 4411 (node /usr/bin/node)"
+        );
+        assert_eq!(
+            error(vec![nginx, node], 2).to_string(),
+            "TCP address 127.0.0.1:8080 is held by PID 80 (nginx /usr/sbin/nginx), \
+             PID 4411 (node /usr/bin/node), and 2 socket(s) in TIME_WAIT state"
+        );
+        assert_eq!(
+            error(vec![], 0).to_string(),
+            "TCP address 127.0.0.1:8080 is held by an unknown process (address in use)"
+        );
+    }
+}
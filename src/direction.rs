@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+
+use crate::{Listener, Protocol, SocketState, platform};
+
+/// The direction of the traffic handled by a [Listener]'s socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum Direction {
+    /// The socket waits for incoming traffic (TCP socket in `LISTEN` state, or unconnected UDP socket).
+    Listening,
+    /// The connection was accepted on a port the same process is listening on (the local side is a server).
+    Inbound,
+    /// The connection was initiated from an ephemeral port (the local side is a client).
+    Outbound,
+    /// The direction can't be determined.
+    Unknown,
+}
+
+impl Direction {
+    /// Computes the [Direction] of each of the given [Listener]s.
+    ///
+    /// The direction is derived by correlating every socket with the `LISTEN` sockets owned by the same process,
+    /// and by checking whether its local port belongs to the ephemeral port range of the system
+    /// (on Linux, this is read from `/proc/sys/net/ipv4/ip_local_port_range`).
+    ///
+    /// # Arguments
+    ///
+    /// * `listeners` - The listeners to classify, usually obtained via [`crate::get_all`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use listeners::Direction;
+    ///
+    /// if let Ok(listeners) = listeners::get_all() {
+    ///     for (l, direction) in Direction::classify(&listeners) {
+    ///         println!("{l} Direction: {direction}");
+    ///     }
+    /// }
+    /// ```
+    pub fn classify(listeners: &HashSet<Listener>) -> HashMap<&Listener, Direction> {
+        classify_with_range(listeners, &platform::ephemeral_port_range())
+    }
+}
+
+fn classify_with_range<'a>(
+    listeners: &'a HashSet<Listener>,
+    ephemeral_ports: &RangeInclusive<u16>,
+) -> HashMap<&'a Listener, Direction> {
+    // listening sockets by PID and port, so each connection is only compared with the ones it may be accepted on
+    let mut listen_sockets: HashMap<(u32, u16), Vec<SocketAddr>> = HashMap::new();
+    for l in listeners
+        .iter()
+        .filter(|l| l.protocol == Protocol::TCP && l.state == SocketState::Listen)
+    {
+        listen_sockets
+            .entry((l.process.pid, l.socket.port()))
+            .or_default()
+            .push(l.socket);
+    }
+
+    listeners
+        .iter()
+        .map(|l| {
+            let direction = match (l.protocol, l.state) {
+                (Protocol::TCP, SocketState::Listen)
+                | (Protocol::UDP, SocketState::Unconnected) => Direction::Listening,
+                (Protocol::TCP, SocketState::SynSent) => Direction::Outbound,
+                (Protocol::TCP, _)
+                    if listen_sockets
+                        .get(&(l.process.pid, l.socket.port()))
+                        .is_some_and(|sockets| sockets.iter().any(|s| accepts(s, &l.socket))) =>
+                {
+                    Direction::Inbound
+                }
+                (Protocol::TCP, _) | (Protocol::UDP, SocketState::Connected)
+                    if ephemeral_ports.contains(&l.socket.port()) =>
+                {
+                    Direction::Outbound
+                }
+                _ => Direction::Unknown,
+            };
+            (l, direction)
+        })
+        .collect()
+}
+
+/// Whether a connection with the given local socket could have been accepted by a socket listening on `listen_socket`.
+///
+/// A socket listening on `[::]` accepts connections of both address families, one listening on `0.0.0.0` only IPv4 ones.
+pub(crate) fn accepts(listen_socket: &SocketAddr, local_socket: &SocketAddr) -> bool {
+    if listen_socket.port() != local_socket.port() {
+        return false;
+    }
+
+    let listen_ip = listen_socket.ip().to_canonical();
+    let local_ip = local_socket.ip().to_canonical();
+    match listen_ip {
+        IpAddr::V4(ip) if ip.is_unspecified() => local_ip.is_ipv4(),
+        IpAddr::V6(ip) if ip.is_unspecified() => true,
+        _ => listen_ip == local_ip,
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Listening => write!(f, "LISTENING"),
+            Direction::Inbound => write!(f, "INBOUND"),
+            Direction::Outbound => write!(f, "OUTBOUND"),
+            Direction::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    use crate::direction::{Direction, accepts, classify_with_range};
+    use crate::{Listener, Protocol, SocketState};
+
+    #[test]
+    fn test_classify() {
+        let listen = Listener::fixture(1, "0.0.0.0:5432", Protocol::TCP, SocketState::Listen);
+        let accepted =
+            Listener::fixture(1, "127.0.0.1:5432", Protocol::TCP, SocketState::Established);
+        let client = Listener::fixture(
+            2,
+            "127.0.0.1:40000",
+            Protocol::TCP,
+            SocketState::Established,
+        );
+        let connecting = Listener::fixture(2, "10.0.0.1:8080", Protocol::TCP, SocketState::SynSent);
+        // same port as the server, but owned by another process
+        let other = Listener::fixture(3, "127.0.0.1:5432", Protocol::TCP, SocketState::Established);
+        let udp_server =
+            Listener::fixture(4, "0.0.0.0:53", Protocol::UDP, SocketState::Unconnected);
+        let udp_client =
+            Listener::fixture(5, "127.0.0.1:50000", Protocol::UDP, SocketState::Connected);
+        let udp_unknown =
+            Listener::fixture(6, "127.0.0.1:53", Protocol::UDP, SocketState::Connected);
+
+        let listeners = HashSet::from([
+            listen.clone(),
+            accepted.clone(),
+            client.clone(),
+            connecting.clone(),
+            other.clone(),
+            udp_server.clone(),
+            udp_client.clone(),
+            udp_unknown.clone(),
+        ]);
+        let directions = classify_with_range(&listeners, &(32768..=60999));
+
+        assert_eq!(directions[&listen], Direction::Listening);
+        assert_eq!(directions[&accepted], Direction::Inbound);
+        assert_eq!(directions[&client], Direction::Outbound);
+        assert_eq!(directions[&connecting], Direction::Outbound);
+        assert_eq!(directions[&other], Direction::Unknown);
+        assert_eq!(directions[&udp_server], Direction::Listening);
+        assert_eq!(directions[&udp_client], Direction::Outbound);
+        assert_eq!(directions[&udp_unknown], Direction::Unknown);
+    }
+
+    #[test]
+    fn test_accepts() {
+        let socket = |s| SocketAddr::from_str(s).unwrap();
+        assert!(accepts(&socket("0.0.0.0:80"), &socket("10.0.0.1:80")));
+        assert!(accepts(&socket("[::]:80"), &socket("[::ffff:10.0.0.1]:80")));
+        assert!(accepts(
+            &socket("10.0.0.1:80"),
+            &socket("[::ffff:10.0.0.1]:80")
+        ));
+        assert!(accepts(&socket("[::1]:80"), &socket("[::1]:80")));
+        assert!(!accepts(&socket("10.0.0.1:80"), &socket("10.0.0.2:80")));
+        assert!(!accepts(&socket("0.0.0.0:80"), &socket("10.0.0.1:81")));
+        // only `[::]` accepts both address families
+        assert!(accepts(&socket("[::]:80"), &socket("10.0.0.1:80")));
+        assert!(accepts(
+            &socket("0.0.0.0:80"),
+            &socket("[::ffff:10.0.0.1]:80")
+        ));
+        assert!(!accepts(&socket("0.0.0.0:80"), &socket("[::1]:80")));
+        assert!(!accepts(&socket("0.0.0.0:80"), &socket("[fe80::1]:80")));
+    }
+}
@@ -0,0 +1,634 @@
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::{Listener, Protocol, SocketState};
+
+/// A filter on [Listener]s, usually parsed from an `ss`-style expression.
+///
+/// The expression syntax supports:
+///
+/// * `tcp`, `udp` - protocol
+/// * `state <STATE>` - socket state, as in its [`Display`] representation (case-insensitive, e.g. `listen`)
+/// * `sport <OP> <PORT>`, `dport <OP> <PORT>` - local and remote port,
+///   where `<OP>` is one of `=`, `!=`, `<`, `<=`, `>`, `>=` (or `eq`, `ne`, `lt`, `le`, `gt`, `ge`),
+///   and can be omitted to match either a single port or an inclusive range like `8000-8999`;
+///   ports can be prefixed with `:` like in `ss`
+/// * `src <CIDR>`, `dst <CIDR>` - local and remote IP address, like `127.0.0.1`, `10.0.0.0/8`, or `::1`
+/// * `process <GLOB>` - process name, where `*` matches any sequence of characters and `?` any single character
+/// * `pid <PID>` - process ID
+/// * `not` (or `!`), `and` (or `&&`), `or` (or `||`), and parentheses, in order of precedence
+///
+/// Values containing spaces or parentheses can be double-quoted (e.g. `process "Google Chrome*"`).
+///
+/// # Example
+///
+/// ```
+/// use listeners::Filter;
+///
+/// let filter: Filter = "tcp and state listen and sport >= 8000 and not src 127.0.0.1"
+///     .parse()
+///     .unwrap();
+/// if let Ok(listeners) = listeners::get_all() {
+///     for l in listeners.iter().filter(|l| filter.matches(l)) {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum Filter {
+    /// Matches sockets using the given protocol.
+    Protocol(Protocol),
+    /// Matches sockets in the given state.
+    State(SocketState),
+    /// Matches sockets whose local port is in the given range.
+    LocalPort(RangeInclusive<u16>),
+    /// Matches sockets whose remote port is in the given range.
+    RemotePort(RangeInclusive<u16>),
+    /// Matches sockets whose local IP address is in the given network.
+    LocalAddress(Cidr),
+    /// Matches sockets whose remote IP address is in the given network.
+    RemoteAddress(Cidr),
+    /// Matches sockets owned by processes whose name (full or as reported by the kernel) matches the given glob pattern.
+    ProcessName(String),
+    /// Matches sockets owned by the given process ID.
+    Pid(u32),
+    /// Matches sockets not matched by the inner filter.
+    Not(Box<Filter>),
+    /// Matches sockets matched by both the inner filters.
+    And(Box<Filter>, Box<Filter>),
+    /// Matches sockets matched by at least one of the inner filters.
+    Or(Box<Filter>, Box<Filter>),
+}
+
+/// An IP network in CIDR notation, like `10.0.0.0/8`.
+///
+/// IPv4-mapped IPv6 addresses are treated as the corresponding IPv4 addresses.
+///
+/// With the `serde` feature, networks are (de)serialized as their [`Display`] representation (e.g. `"10.0.0.0/8"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl Filter {
+    /// Returns whether the given [Listener] is matched by this filter.
+    pub fn matches(&self, listener: &Listener) -> bool {
+        match self {
+            Filter::Protocol(protocol) => listener.protocol == *protocol,
+            Filter::State(state) => listener.state == *state,
+            Filter::LocalPort(ports) => ports.contains(&listener.socket.port()),
+            Filter::RemotePort(ports) => listener.remote.is_some_and(|r| ports.contains(&r.port())),
+            Filter::LocalAddress(cidr) => cidr.contains(listener.socket.ip()),
+            Filter::RemoteAddress(cidr) => listener.remote.is_some_and(|r| cidr.contains(r.ip())),
+            Filter::ProcessName(pattern) => {
+                glob_match(pattern, &listener.process.name)
+                    || glob_match(pattern, &listener.process.comm)
+            }
+            Filter::Pid(pid) => listener.process.pid == *pid,
+            Filter::Not(f) => !f.matches(listener),
+            Filter::And(a, b) => a.matches(listener) && b.matches(listener),
+            Filter::Or(a, b) => a.matches(listener) || b.matches(listener),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Box<dyn std::error::Error>;
+
+    /// Parses a filter expression, as described in [Filter].
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        let filter = parser.parse_or()?;
+        if let Some(token) = parser.next() {
+            return Err(format!("Unexpected {token} in filter").into());
+        }
+        Ok(filter)
+    }
+}
+
+impl Cidr {
+    /// Creates a network from an IP address and a prefix length.
+    ///
+    /// Host bits of the address are cleared.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the prefix length exceeds the address length.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> crate::Result<Cidr> {
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_len {
+            return Err(format!("Invalid prefix length for {addr}: {prefix_len}").into());
+        }
+        let (addr, prefix_len) = match addr {
+            IpAddr::V6(v6) if prefix_len >= 96 => match v6.to_ipv4_mapped() {
+                Some(v4) => (IpAddr::V4(v4), prefix_len - 96),
+                None => (addr, prefix_len),
+            },
+            _ => (addr, prefix_len),
+        };
+        let addr = match addr {
+            IpAddr::V4(v4) => IpAddr::V4((u32::from(v4) & v4_mask(prefix_len)).into()),
+            IpAddr::V6(v6) => IpAddr::V6((u128::from(v6) & v6_mask(prefix_len)).into()),
+        };
+        Ok(Cidr { addr, prefix_len })
+    }
+
+    /// The network address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The number of leading bits identifying the network.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns whether the given IP address belongs to this network.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                u32::from(ip) & v4_mask(self.prefix_len) == u32::from(net)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                u128::from(ip) & v6_mask(self.prefix_len) == u128::from(net)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = Box<dyn std::error::Error>;
+
+    /// Parses a network like `10.0.0.0/8`, or a single address like `127.0.0.1` or `[::1]`.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        let addr = addr
+            .strip_prefix('[')
+            .and_then(|a| a.strip_suffix(']'))
+            .unwrap_or(addr);
+        let addr = IpAddr::from_str(addr).map_err(|_| format!("Invalid IP address: {addr}"))?;
+        let prefix_len = match prefix_len {
+            Some(p) => u8::from_str(p).map_err(|_| format!("Invalid prefix length: {p}"))?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Cidr::new(addr, prefix_len)
+    }
+}
+
+impl Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Cidr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Cidr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Cidr::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+fn v4_mask(prefix_len: u8) -> u32 {
+    u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+fn v6_mask(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+/// Returns whether `s` matches the glob `pattern`,
+/// where `*` matches any sequence of characters and `?` any single character.
+pub(crate) fn glob_match(pattern: &str, s: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = s.chars().collect();
+    let (mut p, mut i) = (0, 0);
+    // position of the last `*` in the pattern, and of the character it's currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, i));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            backtrack = Some((star, matched + 1));
+            p = star + 1;
+            i = matched + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    Word(String),
+    Quoted(String),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::Word(w) => write!(f, "'{w}'"),
+            Token::Quoted(q) => write!(f, "'\"{q}\"'"),
+        }
+    }
+}
+
+fn is_operator(c: char) -> bool {
+    matches!(c, '<' | '>' | '=' | '!' | '&' | '|')
+}
+
+fn tokenize(s: &str) -> crate::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push(Token::LParen);
+        } else if c == ')' {
+            chars.next();
+            tokens.push(Token::RParen);
+        } else if c == '"' {
+            chars.next();
+            let mut quoted = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => quoted.push(c),
+                    None => return Err("Unterminated quoted string in filter".into()),
+                }
+            }
+            tokens.push(Token::Quoted(quoted));
+        } else {
+            let operator = is_operator(c);
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || matches!(c, '(' | ')' | '"') || is_operator(c) != operator {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Consumes the next token if it's a word equal to one of `keywords` (case-insensitive).
+    fn next_if_keyword(&mut self, keywords: &[&str]) -> Option<String> {
+        if let Some(Token::Word(w)) = self.peek()
+            && keywords.iter().any(|k| k.eq_ignore_ascii_case(w))
+        {
+            let keyword = w.to_ascii_lowercase();
+            self.pos += 1;
+            return Some(keyword);
+        }
+        None
+    }
+
+    fn value(&mut self, keyword: &str) -> crate::Result<String> {
+        match self.next() {
+            Some(Token::Word(w) | Token::Quoted(w)) => Ok(w),
+            Some(token) => Err(format!("Expected a value after '{keyword}', found {token}").into()),
+            None => Err(format!("Expected a value after '{keyword}'").into()),
+        }
+    }
+
+    fn parse_or(&mut self) -> crate::Result<Filter> {
+        let mut filter = self.parse_and()?;
+        while self.next_if_keyword(&["or", "||"]).is_some() {
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> crate::Result<Filter> {
+        let mut filter = self.parse_not()?;
+        while self.next_if_keyword(&["and", "&&"]).is_some() {
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_not()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_not(&mut self) -> crate::Result<Filter> {
+        if self.next_if_keyword(&["not", "!"]).is_some() {
+            return Ok(Filter::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> crate::Result<Filter> {
+        let keyword = match self.next() {
+            Some(Token::LParen) => {
+                let filter = self.parse_or()?;
+                return match self.next() {
+                    Some(Token::RParen) => Ok(filter),
+                    Some(token) => Err(format!("Expected ')' in filter, found {token}").into()),
+                    None => Err("Expected ')' at the end of filter".into()),
+                };
+            }
+            Some(Token::Word(w)) => w.to_ascii_lowercase(),
+            Some(token) => return Err(format!("Unexpected {token} in filter").into()),
+            None => return Err("Unexpected end of filter".into()),
+        };
+
+        match keyword.as_str() {
+            "tcp" => Ok(Filter::Protocol(Protocol::TCP)),
+            "udp" => Ok(Filter::Protocol(Protocol::UDP)),
+            "state" => Ok(Filter::State(SocketState::from_str(
+                &self.value(&keyword)?,
+            )?)),
+            "sport" => Ok(self.parse_ports(&keyword, Filter::LocalPort)?),
+            "dport" => Ok(self.parse_ports(&keyword, Filter::RemotePort)?),
+            "src" => Ok(Filter::LocalAddress(Cidr::from_str(
+                &self.value(&keyword)?,
+            )?)),
+            "dst" => Ok(Filter::RemoteAddress(Cidr::from_str(
+                &self.value(&keyword)?,
+            )?)),
+            "process" => Ok(Filter::ProcessName(self.value(&keyword)?)),
+            "pid" => {
+                let pid = self.value(&keyword)?;
+                Ok(Filter::Pid(
+                    u32::from_str(&pid).map_err(|_| format!("Invalid PID: {pid}"))?,
+                ))
+            }
+            _ => Err(format!("Unknown keyword in filter: '{keyword}'").into()),
+        }
+    }
+
+    fn parse_ports(
+        &mut self,
+        keyword: &str,
+        filter: fn(RangeInclusive<u16>) -> Filter,
+    ) -> crate::Result<Filter> {
+        let operator = self.next_if_keyword(&[
+            "=", "==", "eq", "!=", "ne", "<", "lt", "<=", "le", ">", "gt", ">=", "ge",
+        ]);
+        let value = self.value(keyword)?;
+        let value = value.strip_prefix(':').unwrap_or(&value);
+        let parse_port =
+            |p: &str| u16::from_str(p).map_err(|_| format!("Invalid port in filter: {p}"));
+
+        if operator.is_none()
+            && let Some((first, last)) = value.split_once('-')
+        {
+            let (first, last) = (parse_port(first)?, parse_port(last)?);
+            if first > last {
+                return Err(format!("Invalid port range in filter: {value}").into());
+            }
+            return Ok(filter(first..=last));
+        }
+
+        let port = parse_port(value)?;
+        let ports = match operator.as_deref() {
+            None | Some("=" | "==" | "eq") => Some(port..=port),
+            Some("!=" | "ne") => return Ok(Filter::Not(Box::new(filter(port..=port)))),
+            Some("<" | "lt") => port.checked_sub(1).map(|p| 0..=p),
+            Some("<=" | "le") => Some(0..=port),
+            Some(">" | "gt") => port.checked_add(1).map(|p| p..=u16::MAX),
+            _ => Some(port..=u16::MAX),
+        };
+        // comparisons no port can satisfy (e.g. `< 0`) never match
+        Ok(ports.map_or_else(|| Filter::Not(Box::new(filter(0..=u16::MAX))), filter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    use crate::filter::{Cidr, Filter, glob_match};
+    use crate::{Listener, Protocol, SocketState};
+
+    fn parse(s: &str) -> Filter {
+        Filter::from_str(s).unwrap()
+    }
+
+    fn cidr(s: &str) -> Cidr {
+        Cidr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("tcp"), Filter::Protocol(Protocol::TCP));
+        assert_eq!(parse("UDP"), Filter::Protocol(Protocol::UDP));
+        assert_eq!(
+            parse("state time-wait"),
+            Filter::State(SocketState::TimeWait)
+        );
+        assert_eq!(parse("sport 22"), Filter::LocalPort(22..=22));
+        assert_eq!(parse("sport = :22"), Filter::LocalPort(22..=22));
+        assert_eq!(parse("sport 8000-8999"), Filter::LocalPort(8000..=8999));
+        assert_eq!(parse("sport >= 8000"), Filter::LocalPort(8000..=65535));
+        assert_eq!(parse("sport>8000"), Filter::LocalPort(8001..=65535));
+        assert_eq!(parse("dport lt 1024"), Filter::RemotePort(0..=1023));
+        assert_eq!(parse("dport le 1024"), Filter::RemotePort(0..=1024));
+        assert_eq!(
+            parse("dport < 0"),
+            Filter::Not(Box::new(Filter::RemotePort(0..=65535)))
+        );
+        assert_eq!(
+            parse("dport != 443"),
+            Filter::Not(Box::new(Filter::RemotePort(443..=443)))
+        );
+        assert_eq!(
+            parse("src 10.0.0.0/8"),
+            Filter::LocalAddress(cidr("10.0.0.0/8"))
+        );
+        assert_eq!(parse("dst [::1]"), Filter::RemoteAddress(cidr("::1/128")));
+        assert_eq!(
+            parse("process \"Google Chrome*\""),
+            Filter::ProcessName("Google Chrome*".to_string())
+        );
+        assert_eq!(parse("pid 42"), Filter::Pid(42));
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let tcp = || Box::new(Filter::Protocol(Protocol::TCP));
+        let udp = || Box::new(Filter::Protocol(Protocol::UDP));
+        let listen = || Box::new(Filter::State(SocketState::Listen));
+
+        assert_eq!(
+            parse("tcp or udp and state listen"),
+            Filter::Or(tcp(), Box::new(Filter::And(udp(), listen())))
+        );
+        assert_eq!(
+            parse("(tcp || udp) && state listen"),
+            Filter::And(Box::new(Filter::Or(tcp(), udp())), listen())
+        );
+        assert_eq!(
+            parse("not tcp and !state listen"),
+            Filter::And(
+                Box::new(Filter::Not(tcp())),
+                Box::new(Filter::Not(listen()))
+            )
+        );
+        assert_eq!(
+            parse("tcp and state listen and sport >= 8000 and not src 127.0.0.1"),
+            Filter::And(
+                Box::new(Filter::And(
+                    Box::new(Filter::And(tcp(), listen())),
+                    Box::new(Filter::LocalPort(8000..=65535))
+                )),
+                Box::new(Filter::Not(Box::new(Filter::LocalAddress(cidr(
+                    "127.0.0.1"
+                )))))
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for invalid in [
+            "",
+            "tcp and",
+            "tcp udp",
+            "(tcp",
+            "tcp)",
+            "state",
+            "state listening",
+            "sport >= 70000",
+            "sport >= 1-2",
+            "sport 9000-8000",
+            "src 10.0.0.0/33",
+            "src localhost",
+            "pid -1",
+            "process \"unterminated",
+            "\"tcp\"",
+            "sctp",
+        ] {
+            assert!(Filter::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let listener =
+            Listener::fixture(42, "10.1.2.3:8080", Protocol::TCP, SocketState::Established)
+                .connected_to("192.168.1.10:50000")
+                .owned_by("nginx");
+
+        for (filter, expected) in [
+            ("tcp", true),
+            ("udp", false),
+            ("state established", true),
+            ("sport 8000-8999 and dport > 49151", true),
+            ("sport < 8080", false),
+            ("src 10.0.0.0/8 and dst 192.168.0.0/16", true),
+            ("src ::ffff:10.0.0.0/104", true),
+            ("src ::/0", false),
+            ("process ngi?x", true),
+            ("process apache*", false),
+            ("pid 42 and not (udp or state listen)", true),
+        ] {
+            assert_eq!(parse(filter).matches(&listener), expected, "{filter}");
+        }
+
+        let unconnected =
+            Listener::fixture(42, "0.0.0.0:53", Protocol::UDP, SocketState::Unconnected)
+                .owned_by("nginx");
+        assert!(!parse("dport 0-65535").matches(&unconnected));
+        assert!(!parse("dst 0.0.0.0/0").matches(&unconnected));
+    }
+
+    #[test]
+    fn test_cidr() {
+        let net = cidr("192.168.1.77/24");
+        assert_eq!(net.addr(), IpAddr::from_str("192.168.1.0").unwrap());
+        assert_eq!(net.prefix_len(), 24);
+        assert_eq!(net.to_string(), "192.168.1.0/24");
+        assert!(net.contains(IpAddr::from_str("192.168.1.255").unwrap()));
+        assert!(net.contains(IpAddr::from_str("::ffff:192.168.1.1").unwrap()));
+        assert!(!net.contains(IpAddr::from_str("192.168.2.1").unwrap()));
+        assert!(!net.contains(IpAddr::from_str("::1").unwrap()));
+
+        assert!(cidr("0.0.0.0/0").contains(IpAddr::from_str("8.8.8.8").unwrap()));
+        assert!(cidr("fe80::/10").contains(IpAddr::from_str("fe80::1").unwrap()));
+        assert_eq!(cidr("::ffff:10.0.0.1").to_string(), "10.0.0.1/32");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let filter = Filter::from_str("tcp and src 10.0.0.0/8").unwrap();
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            json,
+            "{\"AND\":[{\"PROTOCOL\":\"TCP\"},{\"LOCAL_ADDRESS\":\"10.0.0.0/8\"}]}"
+        );
+        assert_eq!(serde_json::from_str::<Filter>(&json).unwrap(), filter);
+        assert!(serde_json::from_str::<Cidr>("\"10.0.0.0/33\"").is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("nginx", "nginx"));
+        assert!(!glob_match("nginx", "nginx2"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("ng*", "nginx"));
+        assert!(glob_match("*x", "nginx"));
+        assert!(glob_match("n*i*x", "nginx"));
+        assert!(glob_match("?ginx", "nginx"));
+        assert!(!glob_match("?nginx", "nginx"));
+        assert!(glob_match("a*b*c", "aXbXbXc"));
+        assert!(!glob_match("a*b*c", "aXbXbX"));
+    }
+}
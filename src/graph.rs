@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::net::SocketAddr;
+
+use crate::{Direction, Listener, Process, Protocol};
+
+/// TCP connections between processes running on the same host.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionGraph {
+    /// The edges of the graph, sorted by server PID, port, and client PID.
+    pub edges: Vec<ConnectionEdge>,
+}
+
+/// Connections from a client process to a port of a server process, both running on this host.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionEdge {
+    /// The process that initiated the connections.
+    pub client: Process,
+    /// The process that accepted the connections.
+    pub server: Process,
+    /// The port the server accepted the connections on.
+    pub port: u16,
+    /// The number of connections between the two processes on this port.
+    pub connections: usize,
+}
+
+impl ConnectionGraph {
+    /// Builds the graph of the TCP connections whose both ends are owned by processes in the given [Listener]s.
+    ///
+    /// The two ends of a local connection are paired by matching their local and remote sockets.
+    /// The server side is the one accepted on a port its process is listening on (see [`Direction`]).
+    /// An end shared by several processes (e.g. pre-fork servers, or sockets inherited across `fork`)
+    /// is attributed to the one with the lowest PID.
+    ///
+    /// # Arguments
+    ///
+    /// * `listeners` - The listeners to inspect, usually obtained via [`crate::get_all`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use listeners::ConnectionGraph;
+    ///
+    /// if let Ok(listeners) = listeners::get_all() {
+    ///     let graph = ConnectionGraph::build(&listeners);
+    ///     println!("{}", graph.to_dot());
+    /// }
+    /// ```
+    pub fn build(listeners: &HashSet<Listener>) -> ConnectionGraph {
+        let directions = Direction::classify(listeners);
+
+        let mut owners: HashMap<(SocketAddr, SocketAddr), Vec<&Listener>> = HashMap::new();
+        for l in listeners.iter().filter(|l| l.protocol == Protocol::TCP) {
+            if let Some(remote) = l.remote {
+                owners
+                    .entry((canonical(l.socket), canonical(remote)))
+                    .or_default()
+                    .push(l);
+            }
+        }
+        let ends: HashMap<(SocketAddr, SocketAddr), &Listener> = owners
+            .into_iter()
+            .filter_map(|(key, owners)| {
+                let owner = owners
+                    .into_iter()
+                    .min_by_key(|l| (l.process.pid, l.process.start_time))?;
+                Some((key, owner))
+            })
+            .collect();
+
+        let mut edges: HashMap<(&Process, &Process, u16), usize> = HashMap::new();
+        for (&(local, remote), client) in &ends {
+            let Some(server) = ends.get(&(remote, local)) else {
+                continue;
+            };
+            let client_direction = directions.get(client).copied();
+            let server_direction = directions.get(server).copied();
+            let is_client_side = match (client_direction, server_direction) {
+                (Some(Direction::Inbound), _) => false,
+                (_, Some(Direction::Inbound)) => true,
+                (Some(Direction::Outbound), Some(Direction::Outbound)) => false,
+                (Some(Direction::Outbound), _) => true,
+                _ => false,
+            };
+            if is_client_side {
+                *edges
+                    .entry((&client.process, &server.process, server.socket.port()))
+                    .or_insert(0) += 1;
+            }
+        }
+
+        let mut edges: Vec<ConnectionEdge> = edges
+            .into_iter()
+            .map(|((client, server, port), connections)| ConnectionEdge {
+                client: client.clone(),
+                server: server.clone(),
+                port,
+                connections,
+            })
+            .collect();
+        edges.sort_by(|a, b| {
+            (a.server.pid, a.port, a.client.pid).cmp(&(b.server.pid, b.port, b.client.pid))
+        });
+
+        ConnectionGraph { edges }
+    }
+
+    /// Returns the processes appearing in the graph, sorted by PID.
+    pub fn processes(&self) -> Vec<&Process> {
+        let processes: BTreeMap<u32, &Process> = self
+            .edges
+            .iter()
+            .flat_map(|e| [(e.client.pid, &e.client), (e.server.pid, &e.server)])
+            .collect();
+        processes.into_values().collect()
+    }
+
+    /// Exports the graph in Graphviz DOT format.
+    ///
+    /// Nodes are processes (identified by PID), and edges go from clients to servers,
+    /// labelled with the server port and the number of connections.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph listeners {\n");
+        for p in self.processes() {
+            let label = escape(&format!("{} ({})", p.name, p.pid));
+            let _ = writeln!(dot, "    \"{}\" [label=\"{label}\"];", p.pid);
+        }
+        for e in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{} ({})\"];",
+                e.client.pid, e.server.pid, e.port, e.connections
+            );
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// Exports the graph in JSON format.
+    ///
+    /// The output is an object with a `nodes` array (processes with `pid`, `name`, and `path`)
+    /// and an `edges` array (with `client` and `server` PIDs, `port`, and `connections`).
+    ///
+    /// Unlike the `serde` representation of the graph, which repeats the full processes in every edge,
+    /// processes are only listed once as nodes, like in [`ConnectionGraph::to_dot`].
+    /// The JSON is written directly, so this is available without the `serde` feature.
+    pub fn to_json(&self) -> String {
+        let nodes = self
+            .processes()
+            .iter()
+            .map(|p| {
+                format!(
+                    "{{\"pid\":{},\"name\":\"{}\",\"path\":\"{}\"}}",
+                    p.pid,
+                    escape(&p.name),
+                    escape(&p.path)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let edges = self
+            .edges
+            .iter()
+            .map(|e| {
+                format!(
+                    "{{\"client\":{},\"server\":{},\"port\":{},\"connections\":{}}}",
+                    e.client.pid, e.server.pid, e.port, e.connections
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{{\"nodes\":[{nodes}],\"edges\":[{edges}]}}")
+    }
+}
+
+fn canonical(socket: SocketAddr) -> SocketAddr {
+    SocketAddr::new(socket.ip().to_canonical(), socket.port())
+}
+
+/// Escapes a string to be used inside double quotes, both in DOT and JSON.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::graph::{ConnectionGraph, escape};
+    use crate::{Listener, Protocol, SocketState};
+
+    fn listeners() -> HashSet<Listener> {
+        HashSet::from([
+            Listener::fixture(10, "0.0.0.0:5432", Protocol::TCP, SocketState::Listen)
+                .owned_by("postgres"),
+            Listener::fixture(
+                10,
+                "127.0.0.1:5432",
+                Protocol::TCP,
+                SocketState::Established,
+            )
+            .owned_by("postgres")
+            .connected_to("127.0.0.1:40000"),
+            Listener::fixture(
+                10,
+                "127.0.0.1:5432",
+                Protocol::TCP,
+                SocketState::Established,
+            )
+            .owned_by("postgres")
+            .connected_to("127.0.0.1:40001"),
+            Listener::fixture(
+                20,
+                "127.0.0.1:40000",
+                Protocol::TCP,
+                SocketState::Established,
+            )
+            .owned_by("node")
+            .connected_to("127.0.0.1:5432"),
+            Listener::fixture(
+                20,
+                "127.0.0.1:40001",
+                Protocol::TCP,
+                SocketState::Established,
+            )
+            .owned_by("node")
+            .connected_to("127.0.0.1:5432"),
+            // connection to a remote host: no local peer
+            Listener::fixture(
+                20,
+                "10.0.0.1:40002",
+                Protocol::TCP,
+                SocketState::Established,
+            )
+            .owned_by("node")
+            .connected_to("1.1.1.1:443"),
+        ])
+    }
+
+    #[test]
+    fn test_build() {
+        let graph = ConnectionGraph::build(&listeners());
+        assert_eq!(graph.edges.len(), 1);
+        let edge = &graph.edges[0];
+        assert_eq!(edge.client.name, "node");
+        assert_eq!(edge.server.name, "postgres");
+        assert_eq!(edge.port, 5432);
+        assert_eq!(edge.connections, 2);
+    }
+
+    #[test]
+    fn test_build_shared_socket() {
+        // pre-fork workers sharing the listening socket and an accepted connection
+        let mut listeners = listeners();
+        for pid in [11, 12] {
+            listeners.insert(
+                Listener::fixture(pid, "0.0.0.0:5432", Protocol::TCP, SocketState::Listen)
+                    .owned_by("postgres"),
+            );
+            listeners.insert(
+                Listener::fixture(
+                    pid,
+                    "127.0.0.1:5432",
+                    Protocol::TCP,
+                    SocketState::Established,
+                )
+                .owned_by("postgres")
+                .connected_to("127.0.0.1:40000"),
+            );
+        }
+
+        let graph = ConnectionGraph::build(&listeners);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].server.pid, 10);
+        assert_eq!(graph.edges[0].connections, 2);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = ConnectionGraph::build(&listeners());
+        assert_eq!(
+            graph.to_dot(),
+            "digraph listeners {\n    \"10\" [label=\"postgres (10)\"];\n    \"20\" [label=\"node (20)\"];\n    \"20\" -> \"10\" [label=\"5432 (2)\"];\n}"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let graph = ConnectionGraph::build(&listeners());
+        assert_eq!(
+            graph.to_json(),
+            "{\"nodes\":[{\"pid\":10,\"name\":\"postgres\",\"path\":\"/usr/bin/postgres\"},{\"pid\":20,\"name\":\"node\",\"path\":\"/usr/bin/node\"}],\"edges\":[{\"client\":20,\"server\":10,\"port\":5432,\"connections\":2}]}"
+        );
+        assert_eq!(
+            ConnectionGraph::default().to_json(),
+            "{\"nodes\":[],\"edges\":[]}"
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a \"b\" \\ c\n"), "a \\\"b\\\" \\\\ c\\n");
+        assert_eq!(escape("\u{1}"), "\\u0001");
+    }
+}
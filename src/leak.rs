@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use crate::{Listener, Snapshot, get_listeners_by_pids, platform};
+
+/// Detects sockets opened by the current process (and optionally its children) and never closed.
+///
+/// The sockets of the process are recorded when the guard is created;
+/// any socket found on [`SocketLeakGuard::check`], or when the guard is dropped, and not recorded at creation is a leak.
+/// Dropping a guard that detects leaks panics (unless the thread is already panicking),
+/// so that a test leaking sockets fails where the leak happens instead of a later test failing with "address in use".
+///
+/// On Linux, only the descriptors of the current process (`/proc/self/fd`) are inspected,
+/// and children are only tracked on Linux.
+///
+/// # Concurrent tests
+///
+/// `cargo test` runs the tests of a binary as threads of the same process,
+/// so a guard also reports the sockets opened by tests running at the same time, which aren't leaks.
+/// Either run the guarded tests one at a time (e.g. with `#[serial]` from the `serial_test` crate, or `--test-threads=1`),
+/// or create the guard with [`SocketLeakGuard::without_panic`] and inspect [`SocketLeakGuard::leaks`],
+/// taking a new baseline with [`SocketLeakGuard::reset`] right before the code under test.
+///
+/// # Example
+///
+/// ```
+/// use std::net::TcpListener;
+///
+/// use listeners::SocketLeakGuard;
+///
+/// let guard = SocketLeakGuard::new().unwrap();
+/// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+/// assert!(guard.check().is_err());
+/// drop(listener);
+/// assert!(guard.check().is_ok());
+/// ```
+#[derive(Debug)]
+pub struct SocketLeakGuard {
+    baseline: Snapshot,
+    include_children: bool,
+    panic_on_drop: bool,
+}
+
+/// The sockets reported by a [`SocketLeakGuard`], opened after its creation and never closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SocketLeaks {
+    /// The leaked sockets, sorted by local socket, PID, and remote socket.
+    pub listeners: Vec<Listener>,
+}
+
+impl SocketLeakGuard {
+    /// Records the sockets currently owned by the current process.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if it fails to retrieve listeners for the current platform.
+    pub fn new() -> crate::Result<Self> {
+        Self::create(false)
+    }
+
+    /// Records the sockets currently owned by the current process and its descendants.
+    ///
+    /// Sockets of children spawned after the guard creation are tracked too.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if it fails to retrieve listeners for the current platform.
+    pub fn with_children() -> crate::Result<Self> {
+        Self::create(true)
+    }
+
+    fn create(include_children: bool) -> crate::Result<Self> {
+        Ok(SocketLeakGuard {
+            baseline: Snapshot::new(get_tracked_listeners(include_children)?),
+            include_children,
+            panic_on_drop: true,
+        })
+    }
+
+    /// Disables the panic when the guard is dropped with leaks:
+    /// they're then only reported by [`SocketLeakGuard::leaks`] and [`SocketLeakGuard::check`].
+    pub fn without_panic(mut self) -> Self {
+        self.panic_on_drop = false;
+        self
+    }
+
+    /// Records the sockets currently owned again, so that only sockets opened from now on are reported.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if it fails to retrieve listeners for the current platform.
+    pub fn reset(&mut self) -> crate::Result<()> {
+        self.baseline = Snapshot::new(get_tracked_listeners(self.include_children)?);
+        Ok(())
+    }
+
+    /// Returns the sockets opened since the guard creation and still open.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if it fails to retrieve listeners for the current platform.
+    pub fn leaks(&self) -> crate::Result<SocketLeaks> {
+        let current = Snapshot::new(get_tracked_listeners(self.include_children)?);
+        Ok(SocketLeaks {
+            listeners: self.baseline.diff(&current).opened,
+        })
+    }
+
+    /// Checks that every socket opened since the guard creation has been closed.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error describing the leaked sockets, if any,
+    /// or if it fails to retrieve listeners for the current platform.
+    pub fn check(&self) -> crate::Result<()> {
+        let leaks = self.leaks()?;
+        if leaks.is_empty() {
+            Ok(())
+        } else {
+            Err(leaks.to_string().into())
+        }
+    }
+}
+
+impl Drop for SocketLeakGuard {
+    fn drop(&mut self) {
+        if !self.panic_on_drop || std::thread::panicking() {
+            return;
+        }
+        if let Ok(leaks) = self.leaks()
+            && !leaks.is_empty()
+        {
+            panic!("{leaks}");
+        }
+    }
+}
+
+impl SocketLeaks {
+    /// Returns whether no socket was leaked.
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+}
+
+impl Display for SocketLeaks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} socket(s) leaked", self.listeners.len())?;
+        for l in &self.listeners {
+            write!(
+                f,
+                "\n  {} {} {} (PID {})",
+                l.protocol, l.socket, l.state, l.process.pid
+            )?;
+            if let Some(remote) = l.remote {
+                write!(f, " connected to {remote}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn get_tracked_listeners(include_children: bool) -> crate::Result<HashSet<Listener>> {
+    if include_children {
+        get_listeners_by_pids(&platform::get_descendant_pids(std::process::id())?)
+    } else {
+        platform::get_own_listeners()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leak::SocketLeaks;
+    use crate::{Listener, Protocol, SocketState};
+
+    #[test]
+    fn test_socket_leaks_to_string() {
+        let listen = Listener::fixture(42, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+        let connection = Listener::fixture(
+            42,
+            "127.0.0.1:54321",
+            Protocol::TCP,
+            SocketState::Established,
+        )
+        .connected_to("127.0.0.1:8080");
+        let leaks = SocketLeaks {
+            listeners: vec![listen, connection],
+        };
+
+        assert_eq!(
+            leaks.to_string(),
+            "2 socket(s) leaked\n  \
+             TCP 127.0.0.1:8080 LISTEN (PID 42)\n  \
+             TCP 127.0.0.1:54321 ESTABLISHED (PID 42) connected to 127.0.0.1:8080"
+        );
+    }
+}
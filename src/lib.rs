@@ -1,10 +1,44 @@
 #![doc = include_str!("../README.md")]
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
 
+pub use bind::{
+    AddrInUseError, BindCheck, BindOptions, bind_tcp, bind_udp, check_bind, check_bind_with,
+};
+pub use direction::Direction;
+pub use filter::{Cidr, Filter};
+pub use graph::{ConnectionEdge, ConnectionGraph};
+pub use leak::{SocketLeakGuard, SocketLeaks};
+pub use process_info::ProcessInfo;
+pub use process_path::PathUnavailable;
+pub use query::{AddressFamily, ListenerQuery};
+pub use receiver::{Receiver, predict_receiver};
+pub use service::ListeningService;
+pub use snapshot::{DiffMode, Snapshot, SnapshotDiff, StateChange};
+pub use wait::{wait_for_listener, wait_for_port_free};
+pub use watch::{ListenerEvent, Watcher, watch};
+
+mod bind;
+mod direction;
+mod filter;
+mod graph;
+mod leak;
 mod platform;
+mod process_info;
+mod process_path;
+mod query;
+mod receiver;
+mod service;
+mod snapshot;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+mod wait;
+mod watch;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -22,11 +56,14 @@ pub const IS_OS_SUPPORTED: bool = cfg!(any(
 
 /// A process listening on a socket.
 #[derive(Eq, PartialEq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Listener {
     /// The listening process.
     pub process: Process,
     /// The socket this listener is listening on.
     pub socket: SocketAddr,
+    /// The remote socket this listener is connected to, if any.
+    pub remote: Option<SocketAddr>,
     /// The protocol used.
     pub protocol: Protocol,
     /// The state of the socket connection.
@@ -34,18 +71,49 @@ pub struct Listener {
 }
 
 /// An active process.
+///
+/// Two processes are only equal if their start times are equal too,
+/// so that a process isn't mistaken for an older one that had the same PID.
 #[derive(Eq, PartialEq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Process {
     /// Process ID.
     pub pid: u32,
     /// Process name.
+    ///
+    /// On Linux, this is the full program name: the kernel truncates the name of a process to 15 bytes ([`Process::comm`]),
+    /// so a truncated name is completed with the executable or command line it's a prefix of
+    /// (the executable file name, then the program name in the command line, then the script run by an interpreter).
+    /// A process that renamed itself keeps its chosen name.
     pub name: String,
+    /// Process name as reported by the kernel.
+    ///
+    /// On Linux, this is `/proc/<pid>/comm`, which is truncated to 15 bytes; on the other platforms, it's equal to `name`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub comm: String,
     /// Process path.
+    ///
+    /// This is [`Process::exe`] converted to UTF-8 (lossily), or an empty string if the path is unavailable.
     pub path: String,
+    /// Process executable path, preserving paths that aren't valid UTF-8, or the reason why it's unavailable.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "process_path::serde_exe",
+            default = "process_path::serde_exe::default"
+        )
+    )]
+    pub exe: std::result::Result<PathBuf, PathUnavailable>,
+    /// Time the process was started at, identifying it together with its PID.
+    ///
+    /// Only available on Linux, where it's read from `/proc/<pid>/stat`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub start_time: Option<SystemTime>,
 }
 
 /// The network protocol used by a socket.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Protocol {
     /// Transmission Control Protocol.
     TCP,
@@ -54,31 +122,59 @@ pub enum Protocol {
 }
 
 /// The state of a socket connection.
+///
+/// With the `serde` feature, states are (de)serialized as their [`Display`] representation (e.g. `"LISTEN"`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SocketState {
     /// Connection is open and exchanging data.
+    #[cfg_attr(feature = "serde", serde(rename = "ESTABLISHED"))]
     Established,
     /// Initiating a connection.
+    #[cfg_attr(feature = "serde", serde(rename = "SYN_SENT"))]
     SynSent,
     /// Received a connection request.
+    #[cfg_attr(feature = "serde", serde(rename = "SYN_RECEIVED"))]
     SynReceived,
     /// Sent a FIN, waiting for its ACK or the peer's FIN.
+    #[cfg_attr(feature = "serde", serde(rename = "FIN_WAIT_1"))]
     FinWait1,
     /// FIN acknowledged, waiting for the peer's FIN.
+    #[cfg_attr(feature = "serde", serde(rename = "FIN_WAIT_2"))]
     FinWait2,
     /// Waiting for remaining packets after close.
+    #[cfg_attr(feature = "serde", serde(rename = "TIME_WAIT"))]
     TimeWait,
     /// Socket is not connected.
+    #[cfg_attr(feature = "serde", serde(rename = "CLOSED"))]
     Closed,
     /// Received a FIN, waiting to send FIN.
+    #[cfg_attr(feature = "serde", serde(rename = "CLOSE_WAIT"))]
     CloseWait,
     /// Sent FIN, waiting for ACK.
+    #[cfg_attr(feature = "serde", serde(rename = "LAST_ACK"))]
     LastAck,
     /// Listening for incoming connections.
+    #[cfg_attr(feature = "serde", serde(rename = "LISTEN"))]
     Listen,
     /// Both sides sent FIN simultaneously.
+    #[cfg_attr(feature = "serde", serde(rename = "CLOSING"))]
     Closing,
-    /// State is unknown or not applicable (e.g. UDP).
+    /// Received a connection request, tracked by a lightweight request socket (Linux only).
+    #[cfg_attr(feature = "serde", serde(rename = "NEW_SYN_RECV"))]
+    NewSynReceived,
+    /// UDP socket connected to a specific peer.
+    ///
+    /// On Windows, UDP sockets are always reported as [`SocketState::Unknown`].
+    #[cfg_attr(feature = "serde", serde(rename = "CONNECTED"))]
+    Connected,
+    /// UDP socket not connected to any peer (e.g. a UDP server).
+    ///
+    /// On Windows, UDP sockets are always reported as [`SocketState::Unknown`].
+    #[cfg_attr(feature = "serde", serde(rename = "UNCONNECTED"))]
+    Unconnected,
+    /// State is unknown or not applicable.
+    #[cfg_attr(feature = "serde", serde(rename = "UNKNOWN"))]
     Unknown,
 }
 
@@ -97,10 +193,34 @@ impl SocketState {
             Ok(0x09) => Self::LastAck,
             Ok(0x0A) => Self::Listen,
             Ok(0x0B) => Self::Closing,
+            Ok(0x0C) => Self::NewSynReceived,
+            _ => Self::Unknown,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_linux_udp(state_hex: &str) -> Self {
+        match u8::from_str_radix(state_hex, 16) {
+            Ok(0x01) => Self::Connected,
+            Ok(0x07) => Self::Unconnected,
             _ => Self::Unknown,
         }
     }
 
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub(crate) fn from_udp(is_connected: bool) -> Self {
+        if is_connected {
+            Self::Connected
+        } else {
+            Self::Unconnected
+        }
+    }
+
     #[cfg(target_os = "windows")]
     pub(crate) fn from_windows(raw: u32) -> Self {
         match raw {
@@ -157,19 +277,19 @@ impl SocketState {
 ///
 /// Output:
 /// ``` text
-/// PID: 440     Process name: ControlCenter             Socket: 0.0.0.0:0                      Protocol: UDP     State: UNKNOWN
+/// PID: 440     Process name: ControlCenter             Socket: 0.0.0.0:0                      Protocol: UDP     State: UNCONNECTED
 /// PID: 456     Process name: rapportd                  Socket: [::]:49158                     Protocol: TCP     State: LISTEN
 /// PID: 456     Process name: rapportd                  Socket: 0.0.0.0:49158                  Protocol: TCP     State: LISTEN
-/// PID: 456     Process name: rapportd                  Socket: 0.0.0.0:0                      Protocol: UDP     State: UNKNOWN
-/// PID: 485     Process name: sharingd                  Socket: 0.0.0.0:0                      Protocol: UDP     State: UNKNOWN
-/// PID: 516     Process name: WiFiAgent                 Socket: 0.0.0.0:0                      Protocol: UDP     State: UNKNOWN
+/// PID: 456     Process name: rapportd                  Socket: 0.0.0.0:0                      Protocol: UDP     State: UNCONNECTED
+/// PID: 485     Process name: sharingd                  Socket: 0.0.0.0:0                      Protocol: UDP     State: UNCONNECTED
+/// PID: 516     Process name: WiFiAgent                 Socket: 0.0.0.0:0                      Protocol: UDP     State: UNCONNECTED
 /// PID: 1480    Process name: rustrover                 Socket: [::7f00:1]:63342               Protocol: TCP     State: ESTABLISHED
 /// PID: 2123    Process name: Telegram                  Socket: 192.168.1.102:49659            Protocol: TCP     State: ESTABLISHED
 /// PID: 2123    Process name: Telegram                  Socket: 192.168.1.102:49656            Protocol: TCP     State: ESTABLISHED
-/// PID: 2156    Process name: Google Chrome             Socket: 0.0.0.0:0                      Protocol: UDP     State: UNKNOWN
-/// PID: 2167    Process name: Google Chrome Helper      Socket: 192.168.1.102:60834            Protocol: UDP     State: UNKNOWN
-/// PID: 2167    Process name: Google Chrome Helper      Socket: 192.168.1.102:53220            Protocol: UDP     State: UNKNOWN
-/// PID: 2167    Process name: Google Chrome Helper      Socket: 192.168.1.102:59216            Protocol: UDP     State: UNKNOWN
+/// PID: 2156    Process name: Google Chrome             Socket: 0.0.0.0:0                      Protocol: UDP     State: UNCONNECTED
+/// PID: 2167    Process name: Google Chrome Helper      Socket: 192.168.1.102:60834            Protocol: UDP     State: UNCONNECTED
+/// PID: 2167    Process name: Google Chrome Helper      Socket: 192.168.1.102:53220            Protocol: UDP     State: UNCONNECTED
+/// PID: 2167    Process name: Google Chrome Helper      Socket: 192.168.1.102:59216            Protocol: UDP     State: UNCONNECTED
 /// ```
 pub fn get_all() -> Result<HashSet<Listener>> {
     platform::get_all()
@@ -204,19 +324,293 @@ pub fn get_process_by_port(port: u16, protocol: Protocol) -> Result<Process> {
     platform::get_process_by_port(port, protocol)
 }
 
+/// Returns the [Process] bound to the given socket address.
+///
+/// Unlike [`get_process_by_port`], processes bound to the same port on different addresses are told apart
+/// (e.g. `127.0.0.1:8080` and `10.0.0.5:8080`).
+/// A socket bound to exactly the given address is preferred; otherwise, the owner of a wildcard listener
+/// (`0.0.0.0` or `[::]`) that would receive traffic destined for the given address is returned,
+/// following the lookup rules of [`Receiver::predict`].
+///
+/// # Arguments
+///
+/// * `socket` - The socket address to look for.
+/// * `protocol` - The protocol to look for (TCP or UDP).
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform,
+/// or if no process is bound to the given address.
+///
+/// # Example
+///
+/// ```
+/// use std::net::SocketAddr;
+///
+/// use listeners::Protocol;
+///
+/// let socket = SocketAddr::from(([127, 0, 0, 1], 8080));
+/// if let Ok(process) = listeners::get_process_by_socket(socket, Protocol::TCP) {
+///     println!("{process}");
+/// }
+/// ```
+pub fn get_process_by_socket(socket: SocketAddr, protocol: Protocol) -> Result<Process> {
+    if socket.port() == 0 {
+        return Err("Port can't be 0".into());
+    }
+
+    let listeners = ListenerQuery::new()
+        .protocol(protocol)
+        .port(socket.port())
+        .execute()?;
+    find_socket_owner(&listeners, socket, protocol)
+        .ok_or_else(|| "No process found bound to the specified socket".into())
+}
+
+/// Finds the process bound to a socket address among the given listeners.
+///
+/// A socket receiving traffic for exactly that address is preferred, then any socket bound to it
+/// (e.g. an accepted connection), then the wildcard socket that would receive its traffic,
+/// following the lookup rules of [`Receiver::predict`].
+fn find_socket_owner(
+    listeners: &HashSet<Listener>,
+    socket: SocketAddr,
+    protocol: Protocol,
+) -> Option<Process> {
+    let receiver = Receiver::predict(listeners, socket, protocol);
+    if let Some(receiver) = &receiver
+        && receiver.exact
+    {
+        return receiver.listeners.first().map(|l| l.process.clone());
+    }
+
+    let ip = socket.ip().to_canonical();
+    listeners
+        .iter()
+        .filter(|l| l.protocol == protocol && l.socket.port() == socket.port())
+        .filter(|l| l.socket.ip().to_canonical() == ip)
+        .min_by_key(|l| l.process.pid)
+        .or_else(|| receiver.as_ref()?.listeners.first())
+        .map(|l| l.process.clone())
+}
+
+/// Returns the [Listener]s owned by the current process.
+///
+/// On Linux, only the file descriptors of the current process (`/proc/self/fd`) are inspected.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+///
+/// # Example
+///
+/// ```
+/// if let Ok(listeners) = listeners::get_own_listeners() {
+///     for l in listeners {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+pub fn get_own_listeners() -> Result<HashSet<Listener>> {
+    platform::get_own_listeners()
+}
+
+/// Returns the [Listener] of a socket file descriptor owned by the current process.
+///
+/// The descriptor can be borrowed from a standard library socket (e.g. via [`std::os::fd::AsFd::as_fd`]),
+/// or from a raw descriptor received from elsewhere (e.g. passed by systemd socket activation).
+///
+/// On Linux, the socket is identified by its inode in the socket tables;
+/// on the other platforms, by its local and remote addresses among the listeners of the current process.
+///
+/// # Arguments
+///
+/// * `fd` - The socket file descriptor.
+///
+/// # Errors
+///
+/// This function returns an error if the descriptor isn't a TCP or UDP socket,
+/// or if it fails to retrieve listeners for the current platform.
+///
+/// # Example
+///
+/// ```
+/// use std::net::TcpListener;
+/// use std::os::fd::{AsFd, BorrowedFd};
+///
+/// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+/// let l = listeners::describe_fd(listener.as_fd()).unwrap();
+/// println!("{l}");
+///
+/// // a descriptor passed by systemd socket activation (the first one is always 3)
+/// if std::env::var("LISTEN_FDS").is_ok_and(|n| n != "0") {
+///     // SAFETY: systemd keeps the descriptor open for the whole process lifetime
+///     let activated = unsafe { BorrowedFd::borrow_raw(3) };
+///     if let Ok(l) = listeners::describe_fd(activated) {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+#[cfg(unix)]
+pub fn describe_fd(fd: std::os::fd::BorrowedFd<'_>) -> Result<Listener> {
+    platform::describe_fd(fd)
+}
+
+/// Returns the processes bound to each of the given ports, retrieving all of them at once.
+///
+/// Unlike calling [`get_process_by_port`] for each port, socket tables and processes are only inspected once.
+/// Ports that no process is bound to (and port 0) are left out of the returned map.
+/// If several processes use a port, the one listening on it (or receiving on an unconnected UDP socket) is returned
+/// rather than one only holding an accepted or outbound connection.
+///
+/// # Arguments
+///
+/// * `ports` - The ports to look for, each with its protocol (TCP or UDP).
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+///
+/// # Example
+///
+/// ```
+/// use listeners::Protocol;
+///
+/// let ports = [(80, Protocol::TCP), (443, Protocol::TCP), (53, Protocol::UDP)];
+/// if let Ok(processes) = listeners::get_processes_by_ports(&ports) {
+///     for ((port, protocol), process) in processes {
+///         println!("{protocol} {port}: {process}");
+///     }
+/// }
+/// ```
+pub fn get_processes_by_ports(
+    ports: &[(u16, Protocol)],
+) -> Result<HashMap<(u16, Protocol), Process>> {
+    let ports: Vec<(u16, Protocol)> = ports
+        .iter()
+        .copied()
+        .filter(|(port, _)| *port != 0)
+        .collect();
+    if ports.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    platform::get_processes_by_ports(&ports)
+}
+
+/// Returns the [Listener]s owned by a given process.
+///
+/// On Linux, only the file descriptors of the given process are inspected, instead of the ones of every process.
+///
+/// # Arguments
+///
+/// * `pid` - The process ID to look for.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+/// If the process doesn't exist, or it doesn't own any socket, an empty set is returned.
+///
+/// # Example
+///
+/// ```
+/// if let Ok(listeners) = listeners::get_listeners_by_pid(std::process::id()) {
+///     for l in listeners {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+pub fn get_listeners_by_pid(pid: u32) -> Result<HashSet<Listener>> {
+    get_listeners_by_pids(&[pid])
+}
+
+/// Returns the [Listener]s owned by any of the given processes.
+///
+/// See [`get_listeners_by_pid`] for details.
+///
+/// # Arguments
+///
+/// * `pids` - The process IDs to look for.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+pub fn get_listeners_by_pids(pids: &[u32]) -> Result<HashSet<Listener>> {
+    if pids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let query = ListenerQuery {
+        pids: pids.to_vec(),
+        ..ListenerQuery::default()
+    };
+    query.execute()
+}
+
+/// Returns the [Listener]s owned by processes with the given name or executable path.
+///
+/// A pattern containing a `/` is compared to the full executable path, otherwise to the process name;
+/// `*` and `?` wildcards are supported (e.g. `postgres*` or `/usr/lib/postgresql/*`).
+///
+/// On Linux, processes are selected by name or path before their descriptors are inspected,
+/// so that only the matching processes are scanned.
+///
+/// # Arguments
+///
+/// * `name_or_path` - The process name, executable path, or glob pattern to look for.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+/// If no process matches, or the matching processes don't own any socket, an empty set is returned.
+///
+/// # Example
+///
+/// ```
+/// if let Ok(listeners) = listeners::get_listeners_by_process("postgres") {
+///     for l in listeners {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+pub fn get_listeners_by_process(name_or_path: &str) -> Result<HashSet<Listener>> {
+    ListenerQuery::new().process(name_or_path).execute()
+}
+
 impl Listener {
-    fn new(
-        pid: u32,
-        name: String,
-        path: String,
+    /// Creates a listener owned by a process named `proc<pid>`, shared by the unit tests of all modules.
+    #[cfg(test)]
+    pub(crate) fn fixture(pid: u32, socket: &str, protocol: Protocol, state: SocketState) -> Self {
+        let process = Process::new(pid, format!("proc{pid}"), format!("path/to/proc{pid}"));
+        Self::with_process(process, socket.parse().unwrap(), None, protocol, state)
+    }
+
+    /// Sets the remote socket of a [fixture](Listener::fixture).
+    #[cfg(test)]
+    pub(crate) fn connected_to(mut self, remote: &str) -> Self {
+        self.remote = Some(remote.parse().unwrap());
+        self
+    }
+
+    /// Sets the owner of a [fixture](Listener::fixture) to a process named `name`, located at `/usr/bin/<name>`.
+    #[cfg(test)]
+    pub(crate) fn owned_by(mut self, name: &str) -> Self {
+        let pid = self.process.pid;
+        self.process = Process::new(pid, name.to_string(), format!("/usr/bin/{name}"));
+        self
+    }
+
+    pub(crate) fn with_process(
+        process: Process,
         socket: SocketAddr,
+        remote: Option<SocketAddr>,
         protocol: Protocol,
         state: SocketState,
     ) -> Self {
-        let process = Process::new(pid, name, path);
         Self {
             process,
             socket,
+            remote,
             protocol,
             state,
         }
@@ -224,8 +618,34 @@ impl Listener {
 }
 
 impl Process {
+    #[cfg(test)]
     fn new(pid: u32, name: String, path: String) -> Self {
-        Self { pid, name, path }
+        let exe = if path.is_empty() {
+            Err(PathUnavailable::Unknown)
+        } else {
+            Ok(PathBuf::from(path))
+        };
+        Self::with_exe(pid, name, exe)
+    }
+
+    /// Creates a process from its executable path, or the reason why it's unavailable.
+    pub(crate) fn with_exe(
+        pid: u32,
+        name: String,
+        exe: std::result::Result<PathBuf, PathUnavailable>,
+    ) -> Self {
+        let path = exe
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self {
+            pid,
+            comm: name.clone(),
+            name,
+            path,
+            exe,
+            start_time: None,
+        }
     }
 }
 
@@ -236,6 +656,7 @@ impl Display for Listener {
             socket,
             protocol,
             state,
+            ..
         } = self;
         let process = process.to_string();
         let protocol = protocol.to_string();
@@ -260,6 +681,9 @@ impl Display for SocketState {
             SocketState::LastAck => write!(f, "LAST_ACK"),
             SocketState::Listen => write!(f, "LISTEN"),
             SocketState::Closing => write!(f, "CLOSING"),
+            SocketState::NewSynReceived => write!(f, "NEW_SYN_RECV"),
+            SocketState::Connected => write!(f, "CONNECTED"),
+            SocketState::Unconnected => write!(f, "UNCONNECTED"),
             SocketState::Unknown => write!(f, "UNKNOWN"),
         }
     }
@@ -281,22 +705,105 @@ impl Display for Protocol {
     }
 }
 
+impl FromStr for Protocol {
+    type Err = Box<dyn std::error::Error>;
+
+    /// Parses a protocol from its name (case-insensitive).
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "TCP" => Ok(Protocol::TCP),
+            "UDP" => Ok(Protocol::UDP),
+            _ => Err(format!("Invalid protocol: {s}").into()),
+        }
+    }
+}
+
+impl FromStr for SocketState {
+    type Err = Box<dyn std::error::Error>;
+
+    /// Parses a state from its [`Display`] representation (case-insensitive, `-` can be used in place of `_`).
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().replace('-', "_").as_str() {
+            "ESTABLISHED" => Ok(SocketState::Established),
+            "SYN_SENT" => Ok(SocketState::SynSent),
+            "SYN_RECEIVED" => Ok(SocketState::SynReceived),
+            "FIN_WAIT_1" => Ok(SocketState::FinWait1),
+            "FIN_WAIT_2" => Ok(SocketState::FinWait2),
+            "TIME_WAIT" => Ok(SocketState::TimeWait),
+            "CLOSED" => Ok(SocketState::Closed),
+            "CLOSE_WAIT" => Ok(SocketState::CloseWait),
+            "LAST_ACK" => Ok(SocketState::LastAck),
+            "LISTEN" => Ok(SocketState::Listen),
+            "CLOSING" => Ok(SocketState::Closing),
+            "NEW_SYN_RECV" => Ok(SocketState::NewSynReceived),
+            "CONNECTED" => Ok(SocketState::Connected),
+            "UNCONNECTED" => Ok(SocketState::Unconnected),
+            "UNKNOWN" => Ok(SocketState::Unknown),
+            _ => Err(format!("Invalid socket state: {s}").into()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::str::FromStr;
 
-    use crate::{Listener, Process, Protocol, SocketState};
+    use crate::{Listener, Process, Protocol, SocketState, find_socket_owner};
 
     #[test]
-    fn test_v4_listener_to_string() {
-        let listener = Listener::new(
-            455,
-            "rapportd".to_string(),
-            "path/to/rapportd".to_string(),
-            SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 51189),
+    fn test_find_socket_owner() {
+        let listener = Listener::fixture;
+        let owner = |listeners: &[&Listener], socket: SocketAddr, protocol: Protocol| {
+            let listeners: HashSet<Listener> = listeners.iter().map(|l| (*l).clone()).collect();
+            find_socket_owner(&listeners, socket, protocol).map(|p| p.pid)
+        };
+        let v4 = SocketAddr::from_str("127.0.0.1:8080").unwrap();
+        let v6 = SocketAddr::from_str("[::1]:8080").unwrap();
+
+        let exact = listener(1, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+        let accepted = listener(2, "127.0.0.1:8080", Protocol::TCP, SocketState::Established);
+        let wildcard_v4 = listener(3, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let wildcard_v6 = listener(4, "[::]:8080", Protocol::TCP, SocketState::Listen);
+        let mapped = listener(
+            5,
+            "[::ffff:127.0.0.1]:8080",
             Protocol::TCP,
             SocketState::Listen,
         );
+        let other_ip = listener(6, "10.0.0.5:8080", Protocol::TCP, SocketState::Listen);
+        let other_port = listener(7, "127.0.0.1:8081", Protocol::TCP, SocketState::Listen);
+        let wildcard_conn = listener(8, "0.0.0.0:8080", Protocol::TCP, SocketState::Established);
+        let connected_udp = listener(9, "0.0.0.0:8080", Protocol::UDP, SocketState::Connected);
+        let unconnected_udp = listener(10, "0.0.0.0:8080", Protocol::UDP, SocketState::Unconnected);
+
+        let tcp = Protocol::TCP;
+        assert_eq!(owner(&[&accepted, &wildcard_v4, &exact], v4, tcp), Some(1));
+        assert_eq!(owner(&[&mapped], v4, tcp), Some(5));
+        assert_eq!(owner(&[&accepted, &wildcard_v4], v4, tcp), Some(2));
+        assert_eq!(owner(&[&wildcard_v6, &wildcard_v4], v4, tcp), Some(3));
+        assert_eq!(owner(&[&wildcard_v6], v4, tcp), Some(4));
+        assert_eq!(owner(&[&wildcard_v6], v6, tcp), Some(4));
+        assert_eq!(owner(&[&wildcard_v4], v6, tcp), None);
+        assert_eq!(
+            owner(&[&other_ip, &other_port, &wildcard_conn], v4, tcp),
+            None
+        );
+        assert_eq!(owner(&[&exact], v4, Protocol::UDP), None);
+
+        // connected UDP sockets only receive datagrams from their peer
+        assert_eq!(owner(&[&connected_udp], v4, Protocol::UDP), None);
+        assert_eq!(
+            owner(&[&connected_udp, &unconnected_udp], v4, Protocol::UDP),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn test_v4_listener_to_string() {
+        let listener = Listener::fixture(455, "0.0.0.0:51189", Protocol::TCP, SocketState::Listen)
+            .owned_by("rapportd");
         assert_eq!(
             listener.to_string(),
             "PID: 455     Process name: rapportd                  Socket: 0.0.0.0:51189                  Protocol: TCP     State: LISTEN"
@@ -305,17 +812,11 @@ mod tests {
 
     #[test]
     fn test_v6_listener_to_string() {
-        let listener = Listener::new(
-            160,
-            "mysqld".to_string(),
-            "path/to/mysqld".to_string(),
-            SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 3306),
-            Protocol::UDP,
-            SocketState::Unknown,
-        );
+        let listener = Listener::fixture(160, "[::]:3306", Protocol::UDP, SocketState::Unconnected)
+            .owned_by("mysqld");
         assert_eq!(
             listener.to_string(),
-            "PID: 160     Process name: mysqld                    Socket: [::]:3306                      Protocol: UDP     State: UNKNOWN"
+            "PID: 160     Process name: mysqld                    Socket: [::]:3306                      Protocol: UDP     State: UNCONNECTED"
         );
     }
 
@@ -346,12 +847,23 @@ mod tests {
         assert_eq!(SocketState::from_linux("09"), SocketState::LastAck);
         assert_eq!(SocketState::from_linux("0A"), SocketState::Listen);
         assert_eq!(SocketState::from_linux("0B"), SocketState::Closing);
+        assert_eq!(SocketState::from_linux("0C"), SocketState::NewSynReceived);
         // unmapped code, non-hex, and out-of-range values all fall back to Unknown
-        assert_eq!(SocketState::from_linux("0C"), SocketState::Unknown);
+        assert_eq!(SocketState::from_linux("0D"), SocketState::Unknown);
         assert_eq!(SocketState::from_linux("zz"), SocketState::Unknown);
         assert_eq!(SocketState::from_linux("100"), SocketState::Unknown);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_socket_state_from_linux_udp() {
+        assert_eq!(SocketState::from_linux_udp("01"), SocketState::Connected);
+        assert_eq!(SocketState::from_linux_udp("07"), SocketState::Unconnected);
+        // UDP sockets never use the other codes
+        assert_eq!(SocketState::from_linux_udp("0A"), SocketState::Unknown);
+        assert_eq!(SocketState::from_linux_udp("zz"), SocketState::Unknown);
+    }
+
     #[cfg(target_os = "windows")]
     #[test]
     fn test_socket_state_from_windows() {
@@ -390,8 +902,119 @@ mod tests {
         assert_eq!(SocketState::from_bsd(8), SocketState::LastAck);
         assert_eq!(SocketState::from_bsd(9), SocketState::FinWait2);
         assert_eq!(SocketState::from_bsd(10), SocketState::TimeWait);
-        // the -1 UDP sentinel and any out-of-range value fall back to Unknown
+        // negative and out-of-range values fall back to Unknown
         assert_eq!(SocketState::from_bsd(-1), SocketState::Unknown);
         assert_eq!(SocketState::from_bsd(11), SocketState::Unknown);
     }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[test]
+    fn test_socket_state_from_udp() {
+        assert_eq!(SocketState::from_udp(true), SocketState::Connected);
+        assert_eq!(SocketState::from_udp(false), SocketState::Unconnected);
+    }
+
+    #[test]
+    fn test_protocol_from_str() {
+        assert_eq!(Protocol::from_str("TCP").unwrap(), Protocol::TCP);
+        assert_eq!(Protocol::from_str("udp").unwrap(), Protocol::UDP);
+        assert!(Protocol::from_str("sctp").is_err());
+    }
+
+    #[test]
+    fn test_socket_state_from_str() {
+        for state in [
+            SocketState::Established,
+            SocketState::SynSent,
+            SocketState::SynReceived,
+            SocketState::FinWait1,
+            SocketState::FinWait2,
+            SocketState::TimeWait,
+            SocketState::Closed,
+            SocketState::CloseWait,
+            SocketState::LastAck,
+            SocketState::Listen,
+            SocketState::Closing,
+            SocketState::NewSynReceived,
+            SocketState::Connected,
+            SocketState::Unconnected,
+            SocketState::Unknown,
+        ] {
+            assert_eq!(SocketState::from_str(&state.to_string()).unwrap(), state);
+        }
+        assert_eq!(
+            SocketState::from_str("time-wait").unwrap(),
+            SocketState::TimeWait
+        );
+        assert!(SocketState::from_str("LISTENING").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::PathUnavailable;
+
+        let listener = Listener::fixture(8, "127.0.0.1:80", Protocol::TCP, SocketState::FinWait1)
+            .connected_to("127.0.0.1:40000")
+            .owned_by("nginx");
+        let json = serde_json::to_string(&listener).unwrap();
+        assert_eq!(
+            json,
+            "{\"process\":{\"pid\":8,\"name\":\"nginx\",\"comm\":\"nginx\",\"path\":\"/usr/bin/nginx\",\"exe\":{\"Ok\":\"/usr/bin/nginx\"},\"start_time\":null},\"socket\":\"127.0.0.1:80\",\"remote\":\"127.0.0.1:40000\",\"protocol\":\"TCP\",\"state\":\"FIN_WAIT_1\"}"
+        );
+        assert_eq!(serde_json::from_str::<Listener>(&json).unwrap(), listener);
+        // the start time can be omitted
+        assert_eq!(
+            serde_json::from_str::<Listener>(&json.replace(",\"start_time\":null", "")).unwrap(),
+            listener
+        );
+        // so can the executable path, unavailable in older data
+        let json_without_exe = json.replace(",\"exe\":{\"Ok\":\"/usr/bin/nginx\"}", "");
+        assert_eq!(
+            serde_json::from_str::<Listener>(&json_without_exe)
+                .unwrap()
+                .process
+                .exe,
+            Err(PathUnavailable::Unknown)
+        );
+        // the reason why the path is unavailable is preserved
+        let mut kernel_thread = listener.clone();
+        kernel_thread.process.path = String::new();
+        kernel_thread.process.exe = Err(PathUnavailable::KernelThread);
+        let json = serde_json::to_string(&kernel_thread).unwrap();
+        assert!(json.contains("\"path\":\"\",\"exe\":{\"Err\":\"KERNEL_THREAD\"}"));
+        assert_eq!(
+            serde_json::from_str::<Listener>(&json).unwrap(),
+            kernel_thread
+        );
+
+        // states are named after their `Display` representation
+        for state in [
+            SocketState::Established,
+            SocketState::SynSent,
+            SocketState::SynReceived,
+            SocketState::FinWait1,
+            SocketState::FinWait2,
+            SocketState::TimeWait,
+            SocketState::Closed,
+            SocketState::CloseWait,
+            SocketState::LastAck,
+            SocketState::Listen,
+            SocketState::Closing,
+            SocketState::NewSynReceived,
+            SocketState::Connected,
+            SocketState::Unconnected,
+            SocketState::Unknown,
+        ] {
+            assert_eq!(
+                serde_json::to_string(&state).unwrap(),
+                format!("\"{state}\"")
+            );
+        }
+    }
 }
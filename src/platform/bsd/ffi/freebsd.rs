@@ -1,9 +1,11 @@
 use super::{CSocketInfo, SocketInfo, socket_info_list};
 use std::{
     collections::HashMap,
-    ffi::CStr,
+    ffi::{CStr, OsStr},
     io,
     os::raw::{c_char, c_int},
+    os::unix::ffi::OsStrExt,
+    path::PathBuf,
     ptr,
 };
 
@@ -83,14 +85,14 @@ pub(in crate::platform::bsd) fn get_process_name(pid: i32) -> io::Result<String>
     }
 }
 
-pub(in crate::platform::bsd) fn get_process_path(pid: i32) -> io::Result<String> {
+pub(in crate::platform::bsd) fn get_process_path(pid: i32) -> io::Result<PathBuf> {
     unsafe {
         let path_ptr = freebsd_fetch_process_path(pid);
         if path_ptr.is_null() {
             return Err(io::Error::last_os_error());
         }
 
-        let path = CStr::from_ptr(path_ptr).to_string_lossy().into_owned();
+        let path = PathBuf::from(OsStr::from_bytes(CStr::from_ptr(path_ptr).to_bytes()));
 
         libc::free(path_ptr.cast::<libc::c_void>());
 
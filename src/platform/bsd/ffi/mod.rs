@@ -1,5 +1,6 @@
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
+use crate::platform::remote_socket;
 use crate::{Protocol, SocketState};
 
 #[cfg(target_os = "freebsd")]
@@ -17,6 +18,7 @@ pub(super) mod openbsd;
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(super) struct SocketInfo {
     pub(super) address: SocketAddr,
+    pub(super) remote_address: Option<SocketAddr>,
     pub(super) protocol: Protocol,
     pub(super) state: SocketState,
     #[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
@@ -43,28 +45,37 @@ pub(super) struct CSocketInfo {
     protocol: i32,
     port: u16,
     state: i32,
+    remote_address: CSocketAddress,
+    remote_port: u16,
 }
 
-impl From<&CSocketInfo> for SocketInfo {
-    fn from(value: &CSocketInfo) -> Self {
-        let c_sock_addr = &value.address;
-        let ip = if c_sock_addr.family == libc::AF_INET {
-            let octets = unsafe { c_sock_addr.addr.ipv4 };
+impl CSocketAddress {
+    fn ip(&self) -> IpAddr {
+        if self.family == libc::AF_INET {
+            let octets = unsafe { self.addr.ipv4 };
             IpAddr::V4(Ipv4Addr::from(octets))
         } else {
-            let octets = unsafe { c_sock_addr.addr.ipv6 };
+            let octets = unsafe { self.addr.ipv6 };
             IpAddr::V6(Ipv6Addr::from(octets))
-        };
+        }
+    }
+}
 
+impl From<&CSocketInfo> for SocketInfo {
+    fn from(value: &CSocketInfo) -> Self {
         SocketInfo {
             #[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
             kvaddr: value.kvaddr,
-            address: SocketAddr::new(ip, value.port),
+            address: SocketAddr::new(value.address.ip(), value.port),
+            remote_address: remote_socket(value.remote_address.ip(), value.remote_port),
             protocol: match value.protocol {
                 libc::IPPROTO_TCP => Protocol::TCP,
                 _ => Protocol::UDP,
             },
-            state: SocketState::from_bsd(value.state),
+            state: match value.protocol {
+                libc::IPPROTO_TCP => SocketState::from_bsd(value.state),
+                _ => SocketState::from_udp(value.state != 0),
+            },
         }
     }
 }
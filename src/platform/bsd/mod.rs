@@ -1,3 +1,4 @@
+pub(crate) use target_os::ephemeral_port_range;
 pub(crate) use target_os::get_all;
 pub(crate) use target_os::get_process_by_port;
 
@@ -53,7 +53,10 @@ extern "C"
 #endif
         int32_t protocol;
         uint16_t port;
+        /* TCP state for TCP sockets; 1 if connected to a peer, 0 otherwise for UDP sockets */
         int32_t state;
+        socket_address_t remote_address;
+        uint16_t remote_port;
     };
 
 #if defined(__FreeBSD__) || defined(__NetBSD__)
@@ -31,12 +31,13 @@ static size_t count_sockets(struct xinpgen *xig)
  * @sock: Pointer to the socket_info_t structure to fill.
  * @xtp:  Pointer to the TCP control block (xtcpcb) returned by sysctl.
  *
- * Sets the port, protocol, kernel virtual address, and IP address
- * (v4 or v6) of the socket.
+ * Sets the port, protocol, kernel virtual address, state, and local and
+ * remote IP addresses (v4 or v6) of the socket.
  */
 static void fillsock_tcp(struct socket_info_t *sock, struct xtcpcb *xtp)
 {
     sock->port = ntohs(xtp->xt_inp.inp_lport);
+    sock->remote_port = ntohs(xtp->xt_inp.inp_fport);
     sock->protocol = IPPROTO_TCP;
     sock->kvaddr = xtp->xt_inp.xi_socket.xso_so;
     sock->state = xtp->t_state;
@@ -45,11 +46,15 @@ static void fillsock_tcp(struct socket_info_t *sock, struct xtcpcb *xtp)
     {
         sock->address.addr.ipv6 = xtp->xt_inp.in6p_laddr;
         sock->address.family = AF_INET6;
+        sock->remote_address.addr.ipv6 = xtp->xt_inp.in6p_faddr;
+        sock->remote_address.family = AF_INET6;
     }
     else
     {
         sock->address.addr.ipv4 = xtp->xt_inp.inp_laddr;
         sock->address.family = AF_INET;
+        sock->remote_address.addr.ipv4 = xtp->xt_inp.inp_faddr;
+        sock->remote_address.family = AF_INET;
     }
 }
 
@@ -59,25 +64,30 @@ static void fillsock_tcp(struct socket_info_t *sock, struct xtcpcb *xtp)
  * @sock: Pointer to the socket_info_t structure to fill.
  * @xip:  Pointer to the UDP control block (xinpcb) returned by sysctl.
  *
- * Sets the port, protocol, kernel virtual address, and IP address
- * (v4 or v6) of the socket.
+ * Sets the port, protocol, kernel virtual address, connection flag,
+ * and local and remote IP addresses (v4 or v6) of the socket.
  */
 static void fillsock_udp(struct socket_info_t *sock, struct xinpcb *xip)
 {
     sock->port = ntohs(xip->inp_lport);
+    sock->remote_port = ntohs(xip->inp_fport);
     sock->protocol = IPPROTO_UDP;
     sock->kvaddr = xip->xi_socket.xso_so;
-    sock->state = -1;
+    sock->state = xip->inp_fport != 0;
 
     if (xip->inp_vflag & INP_IPV6)
     {
         sock->address.addr.ipv6 = xip->in6p_laddr;
         sock->address.family = AF_INET6;
+        sock->remote_address.addr.ipv6 = xip->in6p_faddr;
+        sock->remote_address.family = AF_INET6;
     }
     else
     {
         sock->address.addr.ipv4 = xip->inp_laddr;
         sock->address.family = AF_INET;
+        sock->remote_address.addr.ipv4 = xip->inp_faddr;
+        sock->remote_address.family = AF_INET;
     }
 }
 
@@ -68,7 +68,8 @@ static size_t count_files(struct kinfo_file *files, size_t filesnum)
  *   - Protocol (TCP/UDP)
  *   - Address family (AF_INET/AF_INET6)
  *   - Kernel virtual address (kvaddr)
- *   - IP address and port
+ *   - Local and remote IP addresses and ports
+ *   - TCP state, or whether the socket is connected for UDP
  *
  * Returns: 0 on success, -1 on failure (errno is set accordingly):
  *            - ENOMEM if memory allocation fails
@@ -111,21 +112,31 @@ static int fetch_sockets_common(struct socket_info_t **list, size_t *nentries, i
         (*list)[idx].protocol = sockets[i].ki_protocol;
         (*list)[idx].address.family = sockets[i].ki_family;
         (*list)[idx].kvaddr = sockets[i].ki_sockaddr;
-        (*list)[idx].state = (sockets[i].ki_protocol == IPPROTO_TCP) ? (int32_t)sockets[i].ki_tstate : -1;
+        (*list)[idx].remote_address.family = sockets[i].ki_family;
 
         if (sockets[i].ki_family == AF_INET)
         {
             struct sockaddr_in *sin = (struct sockaddr_in *)(&sockets[i].ki_src);
             (*list)[idx].address.addr.ipv4 = sin->sin_addr;
             (*list)[idx].port = ntohs(sin->sin_port);
+
+            struct sockaddr_in *din = (struct sockaddr_in *)(&sockets[i].ki_dst);
+            (*list)[idx].remote_address.addr.ipv4 = din->sin_addr;
+            (*list)[idx].remote_port = ntohs(din->sin_port);
         }
         else
         {
             struct sockaddr_in6 *sin6 = (struct sockaddr_in6 *)(&sockets[i].ki_src);
             (*list)[idx].address.addr.ipv6 = sin6->sin6_addr;
             (*list)[idx].port = ntohs(sin6->sin6_port);
+
+            struct sockaddr_in6 *din6 = (struct sockaddr_in6 *)(&sockets[i].ki_dst);
+            (*list)[idx].remote_address.addr.ipv6 = din6->sin6_addr;
+            (*list)[idx].remote_port = ntohs(din6->sin6_port);
         }
 
+        (*list)[idx].state = (sockets[i].ki_protocol == IPPROTO_TCP) ? (int32_t)sockets[i].ki_tstate : (*list)[idx].remote_port != 0;
+
         ++idx;
     }
 
@@ -86,7 +86,13 @@ int openbsd_fetch_sockets_by_pid(pid_t pid, struct socket_info_t **list, size_t
         return 0;
     }
 
-    *list = malloc(*nentries * sizeof(struct proc_info_t));
+    *list = malloc(*nentries * sizeof(struct socket_info_t));
+    if (!*list)
+    {
+        free(buffer);
+        errno = ENOMEM;
+        return -1;
+    }
 
     size_t idx = 0;
     for (size_t i = 0; i < files_num; ++i)
@@ -101,7 +107,10 @@ int openbsd_fetch_sockets_by_pid(pid_t pid, struct socket_info_t **list, size_t
         (*list)[idx].port = ntohs(files[i].inp_lport);
         (*list)[idx].address.family = files[i].so_family;
         memcpy(&((*list)[idx].address.addr), files[i].inp_laddru, sizeof(files[i].inp_laddru));
-        (*list)[idx].state = (files[i].so_protocol == IPPROTO_TCP) ? (int32_t)files[i].t_state : -1;
+        (*list)[idx].remote_port = ntohs(files[i].inp_fport);
+        (*list)[idx].remote_address.family = files[i].so_family;
+        memcpy(&((*list)[idx].remote_address.addr), files[i].inp_faddru, sizeof(files[i].inp_faddru));
+        (*list)[idx].state = (files[i].so_protocol == IPPROTO_TCP) ? (int32_t)files[i].t_state : (*list)[idx].remote_port != 0;
 
         ++idx;
     }
@@ -1,7 +1,8 @@
 use super::ffi::netbsd;
-use super::pid_name_path_cache::ProcNamesPathsCache;
+use super::pid_name_path_cache::{ProcNamesPathsCache, get_process_exe};
 use crate::{Listener, Process, Protocol};
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
 pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
     let mut proc_cache = ProcNamesPathsCache::new();
@@ -19,13 +20,12 @@ pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
 
     for socket in sockets {
         if let Some(pid) = kvaddr_pid_map.get(&socket.kvaddr)
-            && let Some((name, path)) = proc_cache.get(*pid)
+            && let Some((name, exe)) = proc_cache.get(*pid)
         {
-            listeners.insert(Listener::new(
-                (*pid).cast_unsigned(),
-                name,
-                path,
+            listeners.insert(Listener::with_process(
+                Process::with_exe((*pid).cast_unsigned(), name, exe),
                 socket.address,
+                socket.remote_address,
                 socket.protocol,
                 socket.state,
             ));
@@ -59,13 +59,18 @@ pub(crate) fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Resul
         if let Some(pid) = kvaddr_pid_map.get(&socket.kvaddr)
             && let Ok(name) = netbsd::get_process_name(*pid)
         {
-            return Ok(Process::new(
+            return Ok(Process::with_exe(
                 (*pid).cast_unsigned(),
                 name,
-                netbsd::get_process_path(*pid).unwrap_or_default(),
+                get_process_exe(*pid),
             ));
         }
     }
 
     Err("No process found listening on the specified port and protocol".into())
 }
+
+pub(crate) fn ephemeral_port_range() -> RangeInclusive<u16> {
+    // default values of net.inet.ip.anonportmin and net.inet.ip.anonportmax
+    49152..=65535
+}
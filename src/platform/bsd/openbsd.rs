@@ -1,5 +1,6 @@
-use crate::{Listener, Process, Protocol};
+use crate::{Listener, PathUnavailable, Process, Protocol};
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
 use super::ffi::openbsd;
 
@@ -12,11 +13,14 @@ pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
         let sockets = openbsd::get_sockets(process.pid).unwrap_or_default();
 
         for socket in sockets {
-            listeners.insert(Listener::new(
-                process.pid.cast_unsigned(),
-                process.name.clone(),
-                String::new(),
+            listeners.insert(Listener::with_process(
+                Process::with_exe(
+                    process.pid.cast_unsigned(),
+                    process.name.clone(),
+                    Err(PathUnavailable::Unsupported),
+                ),
                 socket.address,
+                socket.remote_address,
                 socket.protocol,
                 socket.state,
             ));
@@ -34,10 +38,10 @@ pub(crate) fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Resul
 
         for socket in sockets {
             if socket.address.port() == port && socket.protocol == protocol {
-                return Ok(Process::new(
+                return Ok(Process::with_exe(
                     process.pid.cast_unsigned(),
                     process.name,
-                    String::new(),
+                    Err(PathUnavailable::Unsupported),
                 ));
             }
         }
@@ -45,3 +49,8 @@ pub(crate) fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Resul
 
     Err("No process found listening on the specified port and protocol".into())
 }
+
+pub(crate) fn ephemeral_port_range() -> RangeInclusive<u16> {
+    // default values of net.inet.ip.porthifirst and net.inet.ip.porthilast
+    49152..=65535
+}
@@ -1,4 +1,7 @@
 use std::collections::{HashMap, hash_map::Entry};
+use std::path::PathBuf;
+
+use crate::PathUnavailable;
 
 #[cfg(target_os = "freebsd")]
 use super::ffi::freebsd as platform;
@@ -7,7 +10,7 @@ use super::ffi::freebsd as platform;
 use super::ffi::netbsd as platform;
 
 pub(super) struct ProcNamesPathsCache {
-    cache: HashMap<i32, Option<(String, String)>>,
+    cache: HashMap<i32, Option<(String, Result<PathBuf, PathUnavailable>)>>,
 }
 
 impl ProcNamesPathsCache {
@@ -17,14 +20,23 @@ impl ProcNamesPathsCache {
         }
     }
 
-    pub(super) fn get(&mut self, pid: i32) -> Option<(String, String)> {
+    pub(super) fn get(&mut self, pid: i32) -> Option<(String, Result<PathBuf, PathUnavailable>)> {
         if let Entry::Vacant(e) = self.cache.entry(pid) {
             let name = platform::get_process_name(pid).ok();
-            let path = Some(platform::get_process_path(pid).unwrap_or_default());
+            let exe = Some(get_process_exe(pid));
 
-            e.insert(name.zip(path));
+            e.insert(name.zip(exe));
         }
 
         self.cache.get(&pid).cloned().flatten()
     }
 }
+
+/// Reads the executable path of a process, or the reason why it's unavailable.
+pub(super) fn get_process_exe(pid: i32) -> Result<PathBuf, PathUnavailable> {
+    // kernel threads belong to the kernel process, which has no executable
+    if pid == 0 {
+        return Err(PathUnavailable::KernelThread);
+    }
+    platform::get_process_path(pid).map_err(|e| PathUnavailable::from_io_error(&e))
+}
@@ -0,0 +1,106 @@
+//! Operations that only Linux implements natively.
+//!
+//! On the other platforms, they're derived from [`get_all`],
+//! or report nothing when the platform backend doesn't expose the needed information
+//! (parent PIDs, sockets without an owner, extended process information).
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use super::{get_all, owners_by_port};
+use crate::{Listener, ListenerQuery, Process, ProcessInfo, Protocol, SocketState};
+
+/// Retrieves listeners repeatedly, without keeping any state across polls.
+pub(crate) struct Poller;
+
+impl Poller {
+    pub(crate) fn new() -> Self {
+        Poller
+    }
+
+    pub(crate) fn poll(&mut self) -> crate::Result<HashSet<Listener>> {
+        get_all()
+    }
+}
+
+/// Retrieves the listeners matched by a query, filtering all of them.
+pub(crate) fn get_listeners(query: &ListenerQuery) -> crate::Result<HashSet<Listener>> {
+    Ok(get_all()?
+        .into_iter()
+        .filter(|l| query.matches(l))
+        .collect())
+}
+
+/// Retrieves the processes owning sockets bound to the given ports, filtering all the listeners.
+pub(crate) fn get_processes_by_ports(
+    ports: &[(u16, Protocol)],
+) -> crate::Result<HashMap<(u16, Protocol), Process>> {
+    Ok(owners_by_port(
+        get_all()?
+            .into_iter()
+            .filter(|l| ports.contains(&(l.socket.port(), l.protocol)))
+            .map(|l| (l.socket.port(), l.protocol, l.state, l.process)),
+    ))
+}
+
+/// Sockets that aren't owned by any process (e.g. in `TIME_WAIT` state) aren't reported.
+pub(crate) fn get_unowned_sockets(_query: &ListenerQuery) -> Vec<(SocketAddr, SocketState)> {
+    Vec::new()
+}
+
+/// Parent PIDs aren't reported.
+pub(crate) fn parent_pid(_pid: u32) -> Option<u32> {
+    None
+}
+
+/// Retrieves the listeners of the current process, filtering all of them.
+pub(crate) fn get_own_listeners() -> crate::Result<HashSet<Listener>> {
+    get_listeners(&ListenerQuery::new().pid(std::process::id()))
+}
+
+/// Parent PIDs aren't reported, so only the given process is returned.
+pub(crate) fn get_descendant_pids(pid: u32) -> crate::Result<Vec<u32>> {
+    Ok(vec![pid])
+}
+
+/// Retrieves the listener of a socket owned by the current process,
+/// matching its protocol, local address, and remote address among the listeners of the current process.
+#[cfg(unix)]
+pub(crate) fn describe_fd(fd: std::os::fd::BorrowedFd) -> crate::Result<Listener> {
+    use std::os::fd::AsRawFd;
+
+    let mut socket_type: libc::c_int = 0;
+    let mut len = libc::socklen_t::try_from(std::mem::size_of::<libc::c_int>())?;
+    let ret = unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            (&raw mut socket_type).cast::<libc::c_void>(),
+            &raw mut len,
+        )
+    };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let protocol = match socket_type {
+        libc::SOCK_STREAM => Protocol::TCP,
+        libc::SOCK_DGRAM => Protocol::UDP,
+        _ => return Err("The file descriptor isn't a TCP or UDP socket".into()),
+    };
+
+    // `getsockname` and `getpeername` work on any kind of socket
+    let socket = std::net::TcpStream::from(fd.try_clone_to_owned()?);
+    let local = socket.local_addr()?;
+    let remote = socket.peer_addr().ok();
+
+    get_own_listeners()?
+        .into_iter()
+        .find(|l| l.protocol == protocol && l.socket == local && l.remote == remote)
+        .ok_or_else(|| "The socket wasn't found among the sockets of the current process".into())
+}
+
+/// Extended process information isn't supported.
+pub(crate) fn get_process_info(_process: &Process) -> crate::Result<ProcessInfo> {
+    Err("Extended process information is only available on Linux".into())
+}
@@ -1,62 +1,208 @@
-use std::collections::HashMap;
-use std::fs::File;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
 use std::os::fd::{AsFd, BorrowedFd, RawFd};
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
 
 use rustix::fs::{Mode, OFlags};
 
 use crate::platform::linux::proc_fd::ProcFd;
-use crate::platform::linux::proc_info::ProcInfo;
-use crate::platform::linux::statics::O_PATH_MAYBE;
+use crate::platform::linux::proc_info::{ProcInfo, full_name, parse_cmdline};
+use crate::platform::linux::statics::{IP_LOCAL_PORT_RANGE, O_PATH_MAYBE, ROOT};
+use crate::query::match_process_pattern;
 
-pub(super) fn build_inode_proc_map() -> crate::Result<HashMap<u64, ProcInfo>> {
-    let proc_fds = ProcFd::get_all()?;
+// indexes of the `/proc/<pid>/stat` fields following the process name
+const STAT_PPID: usize = 1;
+const STAT_SESSION: usize = 3;
+const STAT_TTY_NR: usize = 4;
+const STAT_FLAGS: usize = 6;
+const STAT_STARTTIME: usize = 19;
+
+/// The flag marking kernel threads (`PF_KTHREAD` in `include/linux/sched.h`).
+const PF_KTHREAD: u64 = 0x0020_0000;
+
+/// The boot time of the system, in seconds since the Unix epoch.
+static BOOT_TIME: LazyLock<Option<u64>> = LazyLock::new(|| {
+    let stat = std::fs::read_to_string(Path::new(ROOT).join("stat")).ok()?;
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|secs| u64::from_str(secs.trim()).ok())
+});
+
+pub(super) fn build_inode_proc_map(
+    proc_fds: impl Iterator<Item = ProcFd>,
+) -> HashMap<u64, ProcInfo> {
     let mut map: HashMap<u64, ProcInfo> = HashMap::new();
 
     for proc_fd in proc_fds {
-        let dirfd = proc_fd.as_fd();
-        let path = "fd";
-        let Ok(dir_fd) = rustix::fs::openat(
-            dirfd,
-            path,
-            OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
-            Mode::empty(),
-        ) else {
-            continue;
-        };
-        let Ok(mut dir) = rustix::fs::Dir::read_from(&dir_fd) else {
-            continue;
-        };
-        dir.rewind();
-
-        let mut socket_inodes = Vec::new();
-        for entry in dir.flatten() {
-            let name = entry.file_name().to_string_lossy();
-            if RawFd::from_str(&name).is_ok()
-                && let Ok(socket_inode) = get_socket_inode(dir_fd.as_fd(), name.as_ref())
-            {
-                socket_inodes.push(socket_inode);
+        if let Some((proc_info, socket_inodes)) = get_proc_socket_inodes(&proc_fd) {
+            for inode in socket_inodes {
+                map.insert(inode, proc_info.clone());
             }
         }
+    }
 
-        let Ok(stat) = rustix::fs::openat(
-            proc_fd.as_fd(),
-            "stat",
-            OFlags::RDONLY | OFlags::CLOEXEC,
-            Mode::empty(),
-        ) else {
-            continue;
-        };
+    map
+}
+
+/// Maps the given inodes to the processes owning them, inspecting processes until all are found.
+pub(super) fn find_inodes_procs(
+    proc_fds: impl Iterator<Item = ProcFd>,
+    inodes: &HashSet<u64>,
+) -> HashMap<u64, ProcInfo> {
+    let mut map: HashMap<u64, ProcInfo> = HashMap::new();
 
-        if let Ok(proc_info) = ProcInfo::from_file(File::from(stat)) {
+    for proc_fd in proc_fds {
+        if let Some((proc_info, socket_inodes)) = get_proc_socket_inodes(&proc_fd) {
             for inode in socket_inodes {
-                map.insert(inode, proc_info.clone());
+                if inodes.contains(&inode) {
+                    map.insert(inode, proc_info.clone());
+                }
             }
         }
+        if map.len() == inodes.len() {
+            break;
+        }
     }
 
-    Ok(map)
+    map
+}
+
+pub(super) fn get_proc_socket_inodes(proc_fd: &ProcFd) -> Option<(ProcInfo, Vec<u64>)> {
+    let proc_info = ProcInfo::from_proc_fd(proc_fd).ok()?;
+
+    let dirfd = proc_fd.as_fd();
+    let path = "fd";
+    let dir_fd = rustix::fs::openat(
+        dirfd,
+        path,
+        OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
+        Mode::empty(),
+    )
+    .ok()?;
+    let mut dir = rustix::fs::Dir::read_from(&dir_fd).ok()?;
+    dir.rewind();
+
+    let mut socket_inodes = Vec::new();
+    for entry in dir.flatten() {
+        let name = entry.file_name().to_string_lossy();
+        if RawFd::from_str(&name).is_ok()
+            && let Ok(socket_inode) = get_socket_inode(dir_fd.as_fd(), name.as_ref())
+        {
+            socket_inodes.push(socket_inode);
+        }
+    }
+
+    // discard the sockets if the process exited (and its PID was possibly reused) during the scan
+    if !socket_inodes.is_empty() && !proc_info.is_same_process(proc_fd) {
+        return None;
+    }
+
+    Some((proc_info, socket_inodes))
+}
+
+/// Returns whether a process name or executable path matches any of the given patterns.
+///
+/// Only `comm` is read for name patterns, unless it's truncated and doesn't match,
+/// and `exe` for path patterns.
+/// Files are read relative to the open directory, so they can't belong to another process reusing the same PID.
+pub(super) fn proc_matches_patterns(proc_fd: &ProcFd, patterns: &[String]) -> bool {
+    let Ok(comm) = proc_fd.read_file("comm") else {
+        return false;
+    };
+    let comm = String::from_utf8_lossy(&comm);
+    let comm = comm.strip_suffix('\n').unwrap_or(&comm);
+    let path = || {
+        proc_fd
+            .read_link("exe")
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+    let name = || {
+        full_name(comm, &path(), || {
+            parse_cmdline(&proc_fd.read_file("cmdline").unwrap_or_default())
+        })
+    };
+
+    patterns
+        .iter()
+        .any(|pattern| match_process_pattern(pattern, comm, name, path))
+}
+
+/// Reads the parent PID of a process from `/proc/<pid>/stat`.
+pub(super) fn get_parent_pid(pid: u32) -> Option<u32> {
+    let proc_fd = ProcFd::get_by_pid(pid)?;
+    Stat::read(&proc_fd).ok()?.parent_pid()
+}
+
+/// The content of `/proc/<pid>/stat`.
+pub(super) struct Stat {
+    pub(super) pid: u32,
+    pub(super) comm: String,
+    /// The fields following the process name, starting with the process state.
+    fields: Vec<String>,
+}
+
+impl Stat {
+    /// Reads the `stat` file of the process whose `/proc/<pid>` directory is open.
+    pub(super) fn read(proc_fd: &ProcFd) -> crate::Result<Stat> {
+        Stat::parse(&String::from_utf8_lossy(&proc_fd.read_file("stat")?))
+    }
+
+    fn parse(stat: &str) -> crate::Result<Stat> {
+        // the process name may contain spaces and parentheses, so it ends at the last paren
+        let start_paren = stat.find('(').ok_or("Failed to find opening paren")?;
+        let end_paren = stat.rfind(')').ok_or("Failed to find closing paren")?;
+        let pid = u32::from_str(stat[..start_paren].trim())?;
+        let comm = stat[start_paren + 1..end_paren].to_string();
+        let fields = stat[end_paren + 1..]
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        Ok(Stat { pid, comm, fields })
+    }
+
+    fn field(&self, index: usize) -> Option<u64> {
+        self.fields.get(index).and_then(|f| u64::from_str(f).ok())
+    }
+
+    pub(super) fn parent_pid(&self) -> Option<u32> {
+        self.field(STAT_PPID).and_then(|v| u32::try_from(v).ok())
+    }
+
+    pub(super) fn session(&self) -> Option<u32> {
+        self.field(STAT_SESSION).and_then(|v| u32::try_from(v).ok())
+    }
+
+    /// The device number of the controlling terminal, or `None` if the process has no terminal.
+    pub(super) fn tty(&self) -> Option<u64> {
+        self.field(STAT_TTY_NR).filter(|tty| *tty != 0)
+    }
+
+    pub(super) fn is_kernel_thread(&self) -> bool {
+        self.field(STAT_FLAGS)
+            .is_some_and(|flags| flags & PF_KTHREAD != 0)
+    }
+
+    pub(super) fn start_time(&self) -> Option<SystemTime> {
+        self.field(STAT_STARTTIME).and_then(start_time_from_ticks)
+    }
+}
+
+/// Converts a process start time, in clock ticks since boot, to a [SystemTime].
+fn start_time_from_ticks(ticks: u64) -> Option<SystemTime> {
+    let boot_time = (*BOOT_TIME)?;
+    let ticks_per_second = rustix::param::clock_ticks_per_second();
+    if ticks_per_second == 0 {
+        return None;
+    }
+
+    let since_boot = Duration::from_secs(ticks / ticks_per_second)
+        + Duration::from_nanos((ticks % ticks_per_second) * 1_000_000_000 / ticks_per_second);
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(boot_time) + since_boot)
 }
 
 pub(super) fn get_proc_by_inode(inode: u64) -> crate::Result<ProcInfo> {
@@ -94,16 +240,7 @@ pub(super) fn get_proc_by_inode(inode: u64) -> crate::Result<ProcInfo> {
             continue;
         }
 
-        let Ok(stat) = rustix::fs::openat(
-            proc_fd.as_fd(),
-            "stat",
-            OFlags::RDONLY | OFlags::CLOEXEC,
-            Mode::empty(),
-        ) else {
-            continue;
-        };
-
-        if let Ok(proc_info) = ProcInfo::from_file(File::from(stat)) {
+        if let Ok(proc_info) = ProcInfo::from_proc_fd(&proc_fd) {
             return Ok(proc_info);
         }
     }
@@ -134,3 +271,40 @@ fn get_socket_inode<P: AsRef<Path>>(dir_fd: BorrowedFd, path: P) -> crate::Resul
 
     Err("Not a socket inode".into())
 }
+
+pub(super) fn get_ephemeral_port_range() -> crate::Result<RangeInclusive<u16>> {
+    let range = std::fs::read_to_string(IP_LOCAL_PORT_RANGE)?;
+    let mut ports = range.split_whitespace();
+    let first = u16::from_str(ports.next().ok_or("Failed to get first ephemeral port")?)?;
+    let last = u16::from_str(ports.next().ok_or("Failed to get last ephemeral port")?)?;
+    Ok(first..=last)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::linux::helpers::Stat;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = Stat::parse(
+            "4242 (my (odd) name) S 1 4242 4242 34816 4242 4194560 100 0 0 0 2 1 0 0 20 0 1 0 123456 1000 100\n",
+        )
+        .unwrap();
+        assert_eq!(stat.pid, 4242);
+        assert_eq!(stat.comm, "my (odd) name");
+        assert_eq!(stat.parent_pid(), Some(1));
+        assert_eq!(stat.session(), Some(4242));
+        assert_eq!(stat.tty(), Some(34816));
+        assert!(!stat.is_kernel_thread());
+
+        let kthread =
+            Stat::parse("2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 0 0 0 20 0 1 0 9 0 0")
+                .unwrap();
+        assert_eq!(kthread.parent_pid(), Some(0));
+        assert_eq!(kthread.tty(), None);
+        assert!(kthread.is_kernel_thread());
+
+        assert!(Stat::parse("").is_err());
+        assert!(Stat::parse("abc (name) S 1").is_err());
+    }
+}
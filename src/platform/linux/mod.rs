@@ -1,39 +1,214 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::ops::RangeInclusive;
+use std::os::fd::BorrowedFd;
 
-use helpers::{build_inode_proc_map, get_proc_by_inode};
+use helpers::{
+    build_inode_proc_map, find_inodes_procs, get_ephemeral_port_range, get_parent_pid,
+    get_proc_by_inode, proc_matches_patterns,
+};
+use proc_fd::ProcFd;
+use proc_info::ProcInfo;
 use proto_listener::ProtoListener;
+use rustix::fs::FileType;
+use statics::DEFAULT_EPHEMERAL_PORTS;
 
-use crate::{Listener, Process, Protocol};
+use super::owners_by_port;
+use crate::{Listener, ListenerQuery, Process, Protocol, SocketState};
 
 mod helpers;
+mod poller;
+mod proc_details;
 mod proc_fd;
 mod proc_info;
 mod proto_listener;
 mod statics;
 
+pub(crate) use poller::Poller;
+pub(crate) use proc_details::get_process_info;
+
 pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
     let mut listeners = HashSet::new();
 
-    let inode_proc_map = build_inode_proc_map()?;
+    let inode_proc_map = build_inode_proc_map(ProcFd::get_all()?);
 
     for proto_listener in ProtoListener::get_all()? {
         if let Some(p) = inode_proc_map.get(&proto_listener.inode()) {
-            let listener = Listener::new(
-                p.pid(),
-                p.name(),
-                p.path(),
-                proto_listener.local_addr(),
-                proto_listener.protocol(),
-                proto_listener.state(),
-            );
-            listeners.insert(listener);
+            listeners.insert(to_listener(&proto_listener, p));
         }
     }
 
     Ok(listeners)
 }
 
+pub(crate) fn get_listeners(query: &ListenerQuery) -> crate::Result<HashSet<Listener>> {
+    if !query.pids.is_empty() || !query.processes.is_empty() {
+        // the descriptors of the selected processes are read first, without walking every other process
+        let pids = if query.pids.is_empty() {
+            ProcFd::get_all_pids()?
+        } else {
+            query.pids.clone()
+        };
+        let proc_fds = pids
+            .into_iter()
+            .filter_map(ProcFd::get_by_pid)
+            .filter(|proc_fd| {
+                query.processes.is_empty() || proc_matches_patterns(proc_fd, &query.processes)
+            });
+        return Ok(get_listeners_of(proc_fds, query));
+    }
+
+    let proto_listeners = ProtoListener::get_matching(query);
+    if proto_listeners.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let inodes: HashSet<u64> = proto_listeners.iter().map(ProtoListener::inode).collect();
+    let inode_proc_map = find_inodes_procs(ProcFd::get_all()?, &inodes);
+
+    let listeners = proto_listeners
+        .iter()
+        .filter_map(|proto_listener| {
+            inode_proc_map
+                .get(&proto_listener.inode())
+                .map(|p| to_listener(proto_listener, p))
+        })
+        .collect();
+
+    Ok(listeners)
+}
+
+pub(crate) fn get_own_listeners() -> crate::Result<HashSet<Listener>> {
+    Ok(get_listeners_of(
+        ProcFd::get_self().into_iter(),
+        &ListenerQuery::new(),
+    ))
+}
+
+pub(crate) fn describe_fd(fd: BorrowedFd) -> crate::Result<Listener> {
+    let stat = rustix::fs::fstat(fd)?;
+    if FileType::from_raw_mode(stat.st_mode) != FileType::Socket {
+        return Err("The file descriptor isn't a socket".into());
+    }
+
+    let proto_listener = ProtoListener::get_all()?
+        .into_iter()
+        .find(|l| l.inode() == stat.st_ino)
+        .ok_or("The file descriptor isn't a TCP or UDP socket")?;
+    let proc_fd = ProcFd::get_self().ok_or("Failed to open the current process directory")?;
+    let proc_info = ProcInfo::from_proc_fd(&proc_fd)?;
+    Ok(to_listener(&proto_listener, &proc_info))
+}
+
+pub(crate) fn get_descendant_pids(pid: u32) -> crate::Result<Vec<u32>> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for p in ProcFd::get_all_pids()? {
+        if let Some(parent) = get_parent_pid(p) {
+            children.entry(parent).or_default().push(p);
+        }
+    }
+
+    let mut descendants = vec![pid];
+    let mut visited = HashSet::from([pid]);
+    let mut i = 0;
+    while let Some(ancestor) = descendants.get(i).copied() {
+        for child in children.get(&ancestor).into_iter().flatten() {
+            if visited.insert(*child) {
+                descendants.push(*child);
+            }
+        }
+        i += 1;
+    }
+
+    Ok(descendants)
+}
+
+/// Retrieves the listeners matched by a query among the sockets of the given processes.
+fn get_listeners_of(
+    proc_fds: impl Iterator<Item = ProcFd>,
+    query: &ListenerQuery,
+) -> HashSet<Listener> {
+    let inode_proc_map = build_inode_proc_map(proc_fds);
+    if inode_proc_map.is_empty() {
+        return HashSet::new();
+    }
+
+    ProtoListener::get_matching(query)
+        .iter()
+        .filter_map(|proto_listener| {
+            inode_proc_map
+                .get(&proto_listener.inode())
+                .map(|p| to_listener(proto_listener, p))
+        })
+        .collect()
+}
+
 pub(crate) fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Result<Process> {
     let proto_listener = ProtoListener::get_by_port(port, protocol)?;
-    get_proc_by_inode(proto_listener.inode()).map(|p| Process::new(p.pid(), p.name(), p.path()))
+    get_proc_by_inode(proto_listener.inode()).map(|p| p.to_process())
+}
+
+pub(crate) fn get_processes_by_ports(
+    ports: &[(u16, Protocol)],
+) -> crate::Result<HashMap<(u16, Protocol), Process>> {
+    let keys: HashSet<(u16, Protocol)> = ports.iter().copied().collect();
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut query = ListenerQuery::new();
+    for port in keys.iter().map(|(port, _)| *port).collect::<HashSet<u16>>() {
+        query = query.port(port);
+    }
+    for protocol in [Protocol::TCP, Protocol::UDP] {
+        if keys.iter().any(|(_, p)| *p == protocol) {
+            query = query.protocol(protocol);
+        }
+    }
+
+    // sockets without an inode (e.g. in TIME_WAIT state) aren't owned by any process
+    let proto_listeners: Vec<ProtoListener> = ProtoListener::get_matching(&query)
+        .into_iter()
+        .filter(|l| l.inode() != 0 && keys.contains(&(l.local_addr().port(), l.protocol())))
+        .collect();
+    if proto_listeners.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let inodes: HashSet<u64> = proto_listeners.iter().map(ProtoListener::inode).collect();
+    let inode_proc_map = find_inodes_procs(ProcFd::get_all()?, &inodes);
+
+    let owners = owners_by_port(proto_listeners.iter().filter_map(|l| {
+        inode_proc_map
+            .get(&l.inode())
+            .map(|p| (l.local_addr().port(), l.protocol(), l.state(), p))
+    }));
+    Ok(owners
+        .into_iter()
+        .map(|(key, p)| (key, p.to_process()))
+        .collect())
+}
+
+pub(crate) fn get_unowned_sockets(query: &ListenerQuery) -> Vec<(SocketAddr, SocketState)> {
+    // sockets without an inode (e.g. in TIME_WAIT state) aren't owned by any process
+    ProtoListener::get_matching(query)
+        .into_iter()
+        .filter(|l| l.inode() == 0)
+        .map(|l| (l.local_addr(), l.state()))
+        .collect()
+}
+
+pub(crate) fn parent_pid(pid: u32) -> Option<u32> {
+    get_parent_pid(pid)
+}
+
+pub(crate) fn ephemeral_port_range() -> RangeInclusive<u16> {
+    get_ephemeral_port_range().unwrap_or(DEFAULT_EPHEMERAL_PORTS)
+}
+
+fn to_listener(proto_listener: &ProtoListener, p: &ProcInfo) -> Listener {
+    Listener::with_process(
+        p.to_process(),
+        proto_listener.local_addr(),
+        proto_listener.remote_addr(),
+        proto_listener.protocol(),
+        proto_listener.state(),
+    )
 }
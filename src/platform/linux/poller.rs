@@ -0,0 +1,119 @@
+use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
+
+use crate::Listener;
+use crate::platform::linux::helpers::get_proc_socket_inodes;
+use crate::platform::linux::proc_fd::ProcFd;
+use crate::platform::linux::proc_info::ProcInfo;
+use crate::platform::linux::proto_listener::ProtoListener;
+
+/// Retrieves listeners repeatedly, carrying the inode to process map across polls.
+///
+/// Walking `/proc/<pid>/fd` is the expensive part of retrieving listeners,
+/// so processes are only rescanned when a socket inode can't be resolved via the cached map:
+/// processes that appeared since the last poll are scanned first,
+/// and the other ones only if some inodes are still unresolved after that.
+///
+/// An inode missed by a scan may belong to a file descriptor not installed yet (e.g. racing with `accept`),
+/// so it's looked up among all the processes again at the next polls.
+/// Only after [`FULL_SCAN_ATTEMPTS`] misses it's considered not visible (e.g. owned by another namespace),
+/// and just looked up among the processes that appeared since the last poll.
+pub(crate) struct Poller {
+    inode_proc_map: HashMap<u64, ProcInfo>,
+    scanned_pids: HashSet<u32>,
+    /// The number of scans of all the processes that missed each inode.
+    missed_inodes: HashMap<u64, u8>,
+}
+
+/// The number of scans of all the processes looking up an inode before giving up.
+const FULL_SCAN_ATTEMPTS: u8 = 3;
+
+impl Poller {
+    pub(crate) fn new() -> Self {
+        Poller {
+            inode_proc_map: HashMap::new(),
+            scanned_pids: HashSet::new(),
+            missed_inodes: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn poll(&mut self) -> crate::Result<HashSet<Listener>> {
+        let proto_listeners = ProtoListener::get_all()?;
+        let inodes: HashSet<u64> = proto_listeners.iter().map(ProtoListener::inode).collect();
+
+        let live_pids: HashSet<u32> = ProcFd::get_all_pids()?.into_iter().collect();
+
+        // forget closed sockets, and sockets whose process exited (even if its PID was reused meanwhile)
+        let mut alive: HashMap<(u32, Option<SystemTime>), bool> = HashMap::new();
+        self.inode_proc_map.retain(|inode, p| {
+            inodes.contains(inode)
+                && *alive.entry((p.pid(), p.start_time())).or_insert_with(|| {
+                    live_pids.contains(&p.pid())
+                        && ProcFd::get_by_pid(p.pid())
+                            .is_some_and(|proc_fd| p.is_same_process(&proc_fd))
+                })
+        });
+        // processes reusing a PID are scanned as new ones
+        let reused_pids: HashSet<u32> = alive
+            .into_iter()
+            .filter(|(_, alive)| !alive)
+            .map(|((pid, _), _)| pid)
+            .collect();
+        self.scanned_pids
+            .retain(|pid| live_pids.contains(pid) && !reused_pids.contains(pid));
+
+        let mut unknown_inodes: HashSet<u64> = inodes
+            .into_iter()
+            .filter(|inode| !self.inode_proc_map.contains_key(inode))
+            .collect();
+
+        if !unknown_inodes.is_empty() {
+            let (old, new): (Vec<u32>, Vec<u32>) = live_pids
+                .iter()
+                .partition(|pid| self.scanned_pids.contains(pid));
+            self.scan(&new, &mut unknown_inodes);
+            let full_scan = unknown_inodes.iter().any(|inode| {
+                self.missed_inodes
+                    .get(inode)
+                    .is_none_or(|misses| *misses < FULL_SCAN_ATTEMPTS)
+            });
+            if full_scan {
+                self.scan(&old, &mut unknown_inodes);
+            }
+            self.scanned_pids = live_pids;
+
+            self.missed_inodes
+                .retain(|inode, _| unknown_inodes.contains(inode));
+            if full_scan {
+                for inode in unknown_inodes {
+                    let misses = self.missed_inodes.entry(inode).or_default();
+                    *misses = misses.saturating_add(1);
+                }
+            }
+        } else {
+            self.missed_inodes.clear();
+        }
+
+        let listeners = proto_listeners
+            .iter()
+            .filter_map(|proto_listener| {
+                self.inode_proc_map
+                    .get(&proto_listener.inode())
+                    .map(|p| super::to_listener(proto_listener, p))
+            })
+            .collect();
+
+        Ok(listeners)
+    }
+
+    fn scan(&mut self, pids: &[u32], unknown_inodes: &mut HashSet<u64>) {
+        for proc_fd in pids.iter().filter_map(|pid| ProcFd::get_by_pid(*pid)) {
+            if let Some((proc_info, socket_inodes)) = get_proc_socket_inodes(&proc_fd) {
+                for inode in socket_inodes {
+                    unknown_inodes.remove(&inode);
+                    self.inode_proc_map.insert(inode, proc_info.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use crate::platform::linux::helpers::Stat;
+use crate::platform::linux::proc_fd::ProcFd;
+use crate::platform::linux::proc_info::parse_cmdline_os;
+use crate::{Process, ProcessInfo};
+
+pub(crate) fn get_process_info(process: &Process) -> crate::Result<ProcessInfo> {
+    // files are read through the open directory, so they can't belong to another process reusing the PID
+    let proc_fd = ProcFd::get_by_pid(process.pid).ok_or("The process doesn't exist anymore")?;
+    let stat = read_stat(&proc_fd, process)?;
+
+    let cmdline = parse_cmdline_os(&proc_fd.read_file("cmdline").unwrap_or_default());
+
+    let status =
+        String::from_utf8_lossy(&proc_fd.read_file("status").unwrap_or_default()).into_owned();
+    let ids = |key: &str| -> (Option<u32>, Option<u32>) {
+        let mut values = status
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|v| u32::from_str(v).ok());
+        (values.next().flatten(), values.next().flatten())
+    };
+    let (uid, effective_uid) = ids("Uid:");
+    let (gid, effective_gid) = ids("Gid:");
+
+    let cwd = proc_fd.read_link("cwd").ok();
+
+    // the files of a process that exited meanwhile read as empty, so it's checked to be alive after the last read
+    read_stat(&proc_fd, process)?;
+
+    Ok(ProcessInfo {
+        cmdline,
+        parent_pid: stat.parent_pid(),
+        uid,
+        effective_uid,
+        gid,
+        effective_gid,
+        start_time: process.start_time.or_else(|| stat.start_time()),
+        cwd,
+        session: stat.session(),
+        tty: stat.tty(),
+    })
+}
+
+/// Reads the `stat` file of a process, checking that it's still the given process (if its start time is known).
+fn read_stat(proc_fd: &ProcFd, process: &Process) -> crate::Result<Stat> {
+    let stat = Stat::read(proc_fd)?;
+    if process.start_time.is_some() && stat.start_time() != process.start_time {
+        return Err("The process doesn't exist anymore, its PID was reused".into());
+    }
+    Ok(stat)
+}
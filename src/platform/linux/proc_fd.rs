@@ -1,4 +1,8 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Read;
 use std::os::fd::OwnedFd;
+use std::os::unix::ffi::OsStringExt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -18,7 +22,55 @@ impl ProcFd {
         &self.0
     }
 
+    /// Reads a file of the process directory (e.g. `cmdline`).
+    pub(super) fn read_file(&self, name: &str) -> crate::Result<Vec<u8>> {
+        let file = rustix::fs::openat(
+            &self.0,
+            name,
+            OFlags::RDONLY | OFlags::CLOEXEC,
+            Mode::empty(),
+        )?;
+        let mut buf = Vec::new();
+        File::from(file).read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads a symbolic link of the process directory (e.g. `cwd`).
+    pub(super) fn read_link(&self, name: &str) -> rustix::io::Result<PathBuf> {
+        let path = rustix::fs::readlinkat(&self.0, name, Vec::new())?;
+        Ok(PathBuf::from(OsString::from_vec(path.into_bytes())))
+    }
+
+    pub(super) fn get_by_pid(pid: u32) -> Option<ProcFd> {
+        Self::open(&PathBuf::from(ROOT).join(pid.to_string()))
+    }
+
+    /// Opens the directory of the current process, without listing every process.
+    pub(super) fn get_self() -> Option<ProcFd> {
+        Self::open(&Path::new(ROOT).join("self"))
+    }
+
+    fn open(proc_root: &Path) -> Option<ProcFd> {
+        let flags = OFlags::DIRECTORY | OFlags::CLOEXEC | *O_PATH_MAYBE;
+        let file = rustix::fs::openat(rustix::fs::CWD, proc_root, flags, Mode::empty()).ok()?;
+        Some(ProcFd::new(file))
+    }
+
     pub(super) fn get_all() -> crate::Result<impl Iterator<Item = ProcFd>> {
+        Ok(ProcFdsIter {
+            iter: Self::read_root()?.flatten(),
+        })
+    }
+
+    /// Returns the PIDs of the running processes, without opening their directories.
+    pub(super) fn get_all_pids() -> crate::Result<Vec<u32>> {
+        Ok(Self::read_root()?
+            .flatten()
+            .filter_map(|entry| u32::from_str(&entry.file_name().to_string_lossy()).ok())
+            .collect())
+    }
+
+    fn read_root() -> crate::Result<rustix::fs::Dir> {
         let root = Path::new(ROOT);
         let dir = rustix::fs::openat(
             rustix::fs::CWD,
@@ -26,11 +78,7 @@ impl ProcFd {
             OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
             Mode::empty(),
         )?;
-        let dir = rustix::fs::Dir::read_from(dir)?;
-
-        Ok(ProcFdsIter {
-            iter: dir.flatten(),
-        })
+        Ok(rustix::fs::Dir::read_from(dir)?)
     }
 }
 
@@ -42,20 +90,11 @@ impl Iterator for ProcFdsIter {
     type Item = ProcFd;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let root = Path::new(ROOT);
-
         for entry in self.iter.by_ref() {
-            if let Ok(pid) = i32::from_str(&entry.file_name().to_string_lossy()) {
-                let proc_root = PathBuf::from(root).join(pid.to_string());
-
-                let flags = OFlags::DIRECTORY | OFlags::CLOEXEC | *O_PATH_MAYBE;
-                let Ok(file) =
-                    rustix::fs::openat(rustix::fs::CWD, &proc_root, flags, Mode::empty())
-                else {
-                    continue;
-                };
-
-                return Some(ProcFd::new(file));
+            if let Ok(pid) = u32::from_str(&entry.file_name().to_string_lossy())
+                && let Some(proc_fd) = ProcFd::get_by_pid(pid)
+            {
+                return Some(proc_fd);
             }
         }
 
@@ -1,19 +1,39 @@
-use std::fs;
-use std::fs::File;
-use std::io::Read;
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
 use std::path::PathBuf;
-use std::str::FromStr;
+use std::time::SystemTime;
+
+use crate::platform::linux::helpers::Stat;
+use crate::platform::linux::proc_fd::ProcFd;
+use crate::{PathUnavailable, Process};
 
 #[derive(Clone, Debug)]
 pub(super) struct ProcInfo {
     pid: u32,
     name: String,
-    path: String,
+    comm: String,
+    exe: Result<PathBuf, PathUnavailable>,
+    start_time: Option<SystemTime>,
 }
 
+/// The maximum length of a process name in `/proc/<pid>/comm` and `/proc/<pid>/stat`.
+const COMM_MAX_LEN: usize = 15;
+
 impl ProcInfo {
-    fn new(pid: u32, name: String, path: String) -> Self {
-        ProcInfo { pid, name, path }
+    fn new(
+        pid: u32,
+        name: String,
+        comm: String,
+        exe: Result<PathBuf, PathUnavailable>,
+        start_time: Option<SystemTime>,
+    ) -> Self {
+        ProcInfo {
+            pid,
+            name,
+            comm,
+            exe,
+            start_time,
+        }
     }
 
     pub(super) fn pid(&self) -> u32 {
@@ -24,32 +44,202 @@ impl ProcInfo {
         self.name.clone()
     }
 
-    pub(super) fn path(&self) -> String {
-        self.path.clone()
+    pub(super) fn comm(&self) -> String {
+        self.comm.clone()
+    }
+
+    pub(super) fn exe(&self) -> Result<PathBuf, PathUnavailable> {
+        self.exe.clone()
+    }
+
+    pub(super) fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
+    pub(super) fn to_process(&self) -> Process {
+        let mut process = Process::with_exe(self.pid, self.name(), self.exe());
+        process.comm = self.comm();
+        process.start_time = self.start_time;
+        process
+    }
+
+    /// Reads the info of the process whose `/proc/<pid>` directory is open.
+    ///
+    /// Files are read relative to the open directory, so they can't belong to another process reusing the same PID.
+    pub(super) fn from_proc_fd(proc_fd: &ProcFd) -> crate::Result<Self> {
+        let stat = Stat::read(proc_fd)?;
+
+        let exe = proc_fd.read_link("exe").map_err(|errno| {
+            if stat.is_kernel_thread() {
+                PathUnavailable::KernelThread
+            } else {
+                PathUnavailable::from_io_error(&errno.into())
+            }
+        });
+
+        let path = exe
+            .as_ref()
+            .map(|path| path.to_string_lossy())
+            .unwrap_or_default();
+        let name = full_name(&stat.comm, &path, || {
+            parse_cmdline(&proc_fd.read_file("cmdline").unwrap_or_default())
+        });
+
+        let start_time = stat.start_time();
+        Ok(ProcInfo::new(stat.pid, name, stat.comm, exe, start_time))
+    }
+
+    /// Returns whether the open `/proc/<pid>` directory still refers to this process,
+    /// i.e. whether the process is still alive and its PID wasn't reused.
+    pub(super) fn is_same_process(&self, proc_fd: &ProcFd) -> bool {
+        Stat::read(proc_fd)
+            .is_ok_and(|stat| stat.pid == self.pid && stat.start_time() == self.start_time)
+    }
+}
+
+/// Completes a process name possibly truncated by the kernel (`comm`).
+///
+/// A name shorter than the maximum length isn't truncated, and is returned as is.
+/// Otherwise, the first of these file names starting with it is returned:
+/// the executable, the program in the command line, and the script run by an interpreter
+/// (e.g. `python3 /usr/bin/long-script-name`).
+/// If none does, the process renamed itself, and its name is returned as is.
+pub(super) fn full_name(comm: &str, exe: &str, cmdline: impl FnOnce() -> Vec<String>) -> String {
+    if comm.len() < COMM_MAX_LEN {
+        return comm.to_string();
+    }
+
+    let file_name = |path: &str| -> String {
+        let path = path.strip_suffix(" (deleted)").unwrap_or(path);
+        path.rsplit('/').next().unwrap_or(path).to_string()
+    };
+    let exe_name = file_name(exe);
+    if exe_name.starts_with(comm) {
+        return exe_name;
+    }
+
+    cmdline()
+        .iter()
+        .take(2)
+        .map(|arg| file_name(arg))
+        .find(|arg| arg.starts_with(comm))
+        .unwrap_or_else(|| comm.to_string())
+}
+
+/// Splits the NUL-separated arguments of `/proc/<pid>/cmdline`, converting them lossily to strings.
+pub(super) fn parse_cmdline(buf: &[u8]) -> Vec<String> {
+    parse_cmdline_os(buf)
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Splits the NUL-separated arguments of `/proc/<pid>/cmdline`.
+pub(super) fn parse_cmdline_os(buf: &[u8]) -> Vec<OsString> {
+    buf.split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| OsString::from_vec(arg.to_vec()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    use crate::platform::linux::proc_info::{full_name, parse_cmdline, parse_cmdline_os};
+
+    fn cmdline(args: &[&str]) -> impl FnOnce() -> Vec<String> {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        move || args
     }
 
-    pub(super) fn from_file(mut file: File) -> crate::Result<Self> {
-        // read in entire thing, this is only going to be 1 line
-        let mut buf = Vec::new();
-        file.read_to_end(&mut buf)?;
+    #[test]
+    fn test_full_name() {
+        // names shorter than 15 bytes aren't truncated
+        assert_eq!(
+            full_name("nginx", "/usr/sbin/nginx", || unreachable!()),
+            "nginx"
+        );
+        assert_eq!(
+            full_name("renamed", "/usr/bin/node", || unreachable!()),
+            "renamed"
+        );
 
-        let line = String::from_utf8_lossy(&buf);
-        let buf = line.trim();
+        // executable
+        assert_eq!(
+            full_name(
+                "systemd-resolve",
+                "/usr/lib/systemd/systemd-resolved",
+                || { unreachable!() }
+            ),
+            "systemd-resolved"
+        );
+        assert_eq!(
+            full_name(
+                "gnome-shell-cal",
+                "/usr/libexec/gnome-shell-calendar-server (deleted)",
+                || { unreachable!() }
+            ),
+            "gnome-shell-calendar-server"
+        );
 
-        // find the first opening paren, and split off the first part (pid)
-        let start_paren = buf.find('(').ok_or("Failed to find opening paren")?;
-        let end_paren = buf.rfind(')').ok_or("Failed to find closing paren")?;
-        let pid_s = &buf[..start_paren - 1];
-        let name = buf[start_paren + 1..end_paren].to_string();
+        // program in the command line (e.g. a multi-call binary)
+        assert_eq!(
+            full_name(
+                "containerd-shim",
+                "/usr/bin/containerd",
+                cmdline(&["/usr/bin/containerd-shim-runc-v2", "-namespace", "moby"])
+            ),
+            "containerd-shim-runc-v2"
+        );
 
-        let exe_path = format!("/proc/{pid_s}/exe");
-        let path = fs::read_link(exe_path)
-            .unwrap_or(PathBuf::new())
-            .to_string_lossy()
-            .to_string();
+        // script run by an interpreter
+        assert_eq!(
+            full_name(
+                "networkd-dispat",
+                "/usr/bin/python3.12",
+                cmdline(&[
+                    "/usr/bin/python3",
+                    "/usr/bin/networkd-dispatcher",
+                    "--run-startup-triggers"
+                ])
+            ),
+            "networkd-dispatcher"
+        );
+
+        // process renamed to a 15 bytes name
+        assert_eq!(
+            full_name(
+                "my-worker-no-42",
+                "/usr/bin/node",
+                cmdline(&["node", "worker.js"])
+            ),
+            "my-worker-no-42"
+        );
+        // kernel threads have neither an executable nor a command line
+        assert_eq!(
+            full_name("kworker/u16:0-e", "", cmdline(&[])),
+            "kworker/u16:0-e"
+        );
+    }
 
-        let pid = FromStr::from_str(pid_s)?;
+    #[test]
+    fn test_parse_cmdline() {
+        assert_eq!(
+            parse_cmdline(b"/usr/bin/python3\0-m\0http.server\0"),
+            ["/usr/bin/python3", "-m", "http.server"]
+        );
+        assert!(parse_cmdline(b"").is_empty());
 
-        Ok(ProcInfo::new(pid, name, path))
+        // arguments aren't necessarily UTF-8
+        assert_eq!(
+            parse_cmdline_os(b"cat\0file\xff\0"),
+            [
+                OsString::from("cat"),
+                OsString::from_vec(b"file\xff".to_vec())
+            ]
+        );
+        assert_eq!(parse_cmdline(b"cat\0file\xff\0"), ["cat", "file\u{fffd}"]);
     }
 }
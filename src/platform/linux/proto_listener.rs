@@ -1,4 +1,6 @@
-use crate::{Protocol, SocketState};
+use crate::platform::remote_socket;
+use crate::query::AddressFamily;
+use crate::{ListenerQuery, Protocol, SocketState};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
@@ -7,6 +9,7 @@ use std::str::FromStr;
 #[derive(Debug)]
 pub(super) struct ProtoListener {
     local_addr: SocketAddr,
+    remote_addr: Option<SocketAddr>,
     inode: u64,
     protocol: Protocol,
     state: SocketState,
@@ -17,6 +20,10 @@ impl ProtoListener {
         self.local_addr
     }
 
+    pub(super) fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote_addr
+    }
+
     pub(super) fn inode(&self) -> u64 {
         self.inode
     }
@@ -30,41 +37,43 @@ impl ProtoListener {
     }
 
     pub(super) fn get_all() -> crate::Result<Vec<ProtoListener>> {
+        Ok(Self::get_matching(&ListenerQuery::new()))
+    }
+
+    /// Returns the sockets matched by the query, skipping the tables it excludes.
+    pub(super) fn get_matching(query: &ListenerQuery) -> Vec<ProtoListener> {
         let mut table = Vec::new();
 
-        if let Ok(tcp_table) = File::open("/proc/net/tcp") {
-            for line in BufReader::new(tcp_table).lines().map_while(Result::ok) {
-                if let Ok(l) = ProtoListener::from_protocol_table_entry(&line, Protocol::TCP) {
-                    table.push(l);
-                }
+        for (path, protocol, family) in [
+            ("/proc/net/tcp", Protocol::TCP, AddressFamily::IPv4),
+            ("/proc/net/tcp6", Protocol::TCP, AddressFamily::IPv6),
+            ("/proc/net/udp", Protocol::UDP, AddressFamily::IPv4),
+            ("/proc/net/udp6", Protocol::UDP, AddressFamily::IPv6),
+        ] {
+            if !query.includes(protocol, family) {
+                continue;
             }
-        }
-
-        if let Ok(tcp6_table) = File::open("/proc/net/tcp6") {
-            for line in BufReader::new(tcp6_table).lines().map_while(Result::ok) {
-                if let Ok(l) = ProtoListener::from_protocolv6_table_entry(&line, Protocol::TCP) {
+            let Ok(file) = File::open(path) else {
+                continue;
+            };
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                let entry = match family {
+                    AddressFamily::IPv4 => {
+                        ProtoListener::from_protocol_table_entry(&line, protocol)
+                    }
+                    AddressFamily::IPv6 => {
+                        ProtoListener::from_protocolv6_table_entry(&line, protocol)
+                    }
+                };
+                if let Ok(l) = entry
+                    && query.matches_socket(l.protocol, l.local_addr, l.state)
+                {
                     table.push(l);
                 }
             }
         }
 
-        if let Ok(udp_table) = File::open("/proc/net/udp") {
-            for line in BufReader::new(udp_table).lines().map_while(Result::ok) {
-                if let Ok(l) = ProtoListener::from_protocol_table_entry(&line, Protocol::UDP) {
-                    table.push(l)
-                }
-            }
-        }
-
-        if let Ok(udp6_table) = File::open("/proc/net/udp6") {
-            for line in BufReader::new(udp6_table).lines().map_while(Result::ok) {
-                if let Ok(l) = ProtoListener::from_protocolv6_table_entry(&line, Protocol::UDP) {
-                    table.push(l)
-                }
-            }
-        }
-
-        Ok(table)
+        table
     }
 
     pub(super) fn get_by_port(port: u16, protocol: Protocol) -> crate::Result<ProtoListener> {
@@ -122,28 +131,22 @@ impl ProtoListener {
         let mut s = line.split_whitespace();
 
         let local_addr_hex = s.nth(1).ok_or("Failed to get local address")?;
-        let state_hex = s.nth(1).ok_or("Failed to get state")?;
+        let remote_addr_hex = s.next().ok_or("Failed to get remote address")?;
+        let state_hex = s.next().ok_or("Failed to get state")?;
         let state = match protocol {
             Protocol::TCP => SocketState::from_linux(state_hex),
-            Protocol::UDP => SocketState::Unknown,
+            Protocol::UDP => SocketState::from_linux_udp(state_hex),
         };
 
-        let local_ip_port = local_addr_hex
-            .split(':')
-            .flat_map(|s| u32::from_str_radix(s, 16))
-            .collect::<Vec<u32>>();
-
-        let ip_n = local_ip_port.first().ok_or("Failed to get IP")?;
-        let port_n = local_ip_port.get(1).ok_or("Failed to get port")?;
-        let ip = Ipv4Addr::from(u32::from_be(*ip_n));
-        let port = u16::try_from(*port_n)?;
-        let local_addr = SocketAddr::new(IpAddr::V4(ip), port);
+        let local_addr = Self::parse_v4_addr(local_addr_hex)?;
+        let remote_addr = Self::parse_v4_addr(remote_addr_hex)?;
 
         let inode_n = s.nth(5).ok_or("Failed to get inode")?;
         let inode = u64::from_str(inode_n)?;
 
         Ok(Self {
             local_addr,
+            remote_addr: remote_socket(remote_addr.ip(), remote_addr.port()),
             inode,
             protocol,
             state,
@@ -151,24 +154,54 @@ impl ProtoListener {
     }
 
     fn from_protocolv6_table_entry(line: &str, protocol: Protocol) -> crate::Result<Self> {
-        #[cfg(target_endian = "little")]
-        let read_endian = u32::from_le_bytes;
-        #[cfg(target_endian = "big")]
-        let read_endian = u32::from_be_bytes;
-
         let mut s = line.split_whitespace();
 
         let local_addr_hex = s.nth(1).ok_or("Failed to get local address")?;
-        let state_hex = s.nth(1).ok_or("Failed to get state")?;
+        let remote_addr_hex = s.next().ok_or("Failed to get remote address")?;
+        let state_hex = s.next().ok_or("Failed to get state")?;
         let state = match protocol {
             Protocol::TCP => SocketState::from_linux(state_hex),
-            Protocol::UDP => SocketState::Unknown,
+            Protocol::UDP => SocketState::from_linux_udp(state_hex),
         };
 
-        let mut local_ip_port = local_addr_hex.split(':');
+        let local_addr = Self::parse_v6_addr(local_addr_hex)?;
+        let remote_addr = Self::parse_v6_addr(remote_addr_hex)?;
+
+        let inode_n = s.nth(5).ok_or("Failed to get inode")?;
+        let inode = u64::from_str(inode_n)?;
+
+        Ok(Self {
+            local_addr,
+            remote_addr: remote_socket(remote_addr.ip(), remote_addr.port()),
+            inode,
+            protocol,
+            state,
+        })
+    }
 
-        let ip_str = local_ip_port.next().ok_or("Failed to get IP")?;
-        let port_str = local_ip_port.next().ok_or("Failed to get port")?;
+    fn parse_v4_addr(addr_hex: &str) -> crate::Result<SocketAddr> {
+        let ip_port = addr_hex
+            .split(':')
+            .flat_map(|s| u32::from_str_radix(s, 16))
+            .collect::<Vec<u32>>();
+
+        let ip_n = ip_port.first().ok_or("Failed to get IP")?;
+        let port_n = ip_port.get(1).ok_or("Failed to get port")?;
+        let ip = Ipv4Addr::from(u32::from_be(*ip_n));
+        let port = u16::try_from(*port_n)?;
+        Ok(SocketAddr::new(IpAddr::V4(ip), port))
+    }
+
+    fn parse_v6_addr(addr_hex: &str) -> crate::Result<SocketAddr> {
+        #[cfg(target_endian = "little")]
+        let read_endian = u32::from_le_bytes;
+        #[cfg(target_endian = "big")]
+        let read_endian = u32::from_be_bytes;
+
+        let mut ip_port = addr_hex.split(':');
+
+        let ip_str = ip_port.next().ok_or("Failed to get IP")?;
+        let port_str = ip_port.next().ok_or("Failed to get port")?;
 
         if ip_str.len() % 2 != 0 {
             return Err("Invalid IP address".into());
@@ -177,6 +210,9 @@ impl ProtoListener {
             .step_by(2)
             .flat_map(|i| u8::from_str_radix(&ip_str[i..i + 2], 16))
             .collect::<Vec<u8>>();
+        if bytes.len() != 16 {
+            return Err("Invalid IP address".into());
+        }
         let ip_a = read_endian(bytes[0..4].try_into()?);
         let ip_b = read_endian(bytes[4..8].try_into()?);
         let ip_c = read_endian(bytes[8..12].try_into()?);
@@ -193,16 +229,6 @@ impl ProtoListener {
         );
 
         let port = u16::from_str_radix(port_str, 16)?;
-        let local_addr = SocketAddr::new(IpAddr::V6(ip), port);
-
-        let inode_n = s.nth(5).ok_or("Failed to get inode")?;
-        let inode = u64::from_str(inode_n)?;
-
-        Ok(Self {
-            local_addr,
-            inode,
-            protocol,
-            state,
-        })
+        Ok(SocketAddr::new(IpAddr::V6(ip), port))
     }
 }
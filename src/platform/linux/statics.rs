@@ -1,7 +1,14 @@
+use std::ops::RangeInclusive;
+
 use rustix::fs::OFlags;
 
 pub(super) const ROOT: &str = "/proc";
 
+pub(super) const IP_LOCAL_PORT_RANGE: &str = "/proc/sys/net/ipv4/ip_local_port_range";
+
+// default value of net.ipv4.ip_local_port_range
+pub(super) const DEFAULT_EPHEMERAL_PORTS: RangeInclusive<u16> = 32768..=60999;
+
 pub(super) static O_PATH_MAYBE: std::sync::LazyLock<OFlags> = std::sync::LazyLock::new(|| {
     let kernel = std::fs::read_to_string("/proc/sys/kernel/osrelease")
         .map(|s| s.trim().to_owned())
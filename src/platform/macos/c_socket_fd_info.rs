@@ -4,6 +4,7 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use byteorder::{ByteOrder, NetworkEndian};
 
 use crate::platform::macos::proto_listener::ProtoListener;
+use crate::platform::remote_socket;
 use crate::{Protocol, SocketState};
 
 use super::statics::{IPPROTO_TCP, IPPROTO_UDP};
@@ -29,18 +30,24 @@ impl CSocketFdInfo {
                         SocketState::from_bsd(tcp_info.tcpsi_state),
                     )
                 }
-                IPPROTO_UDP => (sock_info.soi_proto.pri_in, SocketState::Unknown),
+                IPPROTO_UDP => {
+                    let udp_info = sock_info.soi_proto.pri_in;
+                    (udp_info, SocketState::from_udp(udp_info.insi_fport != 0))
+                }
                 _ => return Err("Unsupported protocol".into()),
             }
         };
 
         let lport_bytes: [u8; 4] = i32::to_le_bytes(general_sock_info.insi_lport);
+        let fport_bytes: [u8; 4] = i32::to_le_bytes(general_sock_info.insi_fport);
         let local_address = Self::get_local_addr(family, general_sock_info)?;
+        let remote_address = Self::get_remote_addr(family, general_sock_info)?;
         let protocol = Self::get_protocol(family, transport_protocol)?;
 
         let socket_info = ProtoListener::new(
             local_address,
             NetworkEndian::read_u16(&lport_bytes),
+            remote_socket(remote_address, NetworkEndian::read_u16(&fport_bytes)),
             protocol,
             state,
         );
@@ -66,6 +73,24 @@ impl CSocketFdInfo {
         }
     }
 
+    fn get_remote_addr(family: c_int, tcp_sockaddr_in: InSockinfo) -> crate::Result<IpAddr> {
+        match family {
+            2 => {
+                // AF_INET
+                let addr = unsafe { tcp_sockaddr_in.insi_faddr.ina_46.i46a_addr4.s_addr };
+                Ok(IpAddr::V4(Ipv4Addr::from(u32::from_be(addr))))
+            }
+            30 => {
+                // AF_INET6
+                let addr = unsafe { &tcp_sockaddr_in.insi_faddr.ina_6.__u6_addr.__u6_addr8 };
+                let mut ipv6_addr = [0_u16; 8];
+                NetworkEndian::read_u16_into(addr, &mut ipv6_addr);
+                Ok(IpAddr::V6(Ipv6Addr::from(ipv6_addr)))
+            }
+            _ => Err("Unsupported socket family".into()),
+        }
+    }
+
     fn get_protocol(family: c_int, ip_protocol: c_int) -> crate::Result<Protocol> {
         match (family, ip_protocol) {
             (2 | 30, IPPROTO_TCP) => Ok(Protocol::TCP),
@@ -1,9 +1,11 @@
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
 use proc_name::ProcName;
 use proc_pid::ProcPid;
 use proto_listener::ProtoListener;
 use socket_fd::SocketFd;
+use statics::EPHEMERAL_PORTS;
 
 use crate::platform::macos::proc_name::ProcNamesCache;
 use crate::platform::macos::proc_path::ProcPath;
@@ -34,12 +36,11 @@ pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
             if let Ok(proto_listener) = ProtoListener::from_pid_fd(pid, fd)
                 && let Some(ProcName(name)) = proc_names_cache.get(pid)
             {
-                let ProcPath(path) = proc_paths_cache.get(pid);
-                let listener = Listener::new(
-                    pid_u_32,
-                    name,
-                    path,
+                let ProcPath(exe) = proc_paths_cache.get(pid);
+                let listener = Listener::with_process(
+                    Process::with_exe(pid_u_32, name, exe),
                     proto_listener.socket_addr(),
+                    proto_listener.remote_addr(),
                     proto_listener.protocol(),
                     proto_listener.state(),
                 );
@@ -62,11 +63,15 @@ pub(crate) fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Resul
                 && proto_listener.protocol() == protocol
                 && let Ok(ProcName(name)) = ProcName::from_pid(pid)
             {
-                let ProcPath(path) = ProcPath::from_pid(pid);
-                return Ok(Process::new(pid_u_32, name, path));
+                let ProcPath(exe) = ProcPath::from_pid(pid);
+                return Ok(Process::with_exe(pid_u_32, name, exe));
             }
         }
     }
 
     Err("No process found listening on the specified port and protocol".into())
 }
+
+pub(crate) fn ephemeral_port_range() -> RangeInclusive<u16> {
+    EPHEMERAL_PORTS
+}
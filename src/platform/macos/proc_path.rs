@@ -1,20 +1,28 @@
 use std::collections::HashMap;
-use std::collections::hash_map::Entry;
-use std::ffi::c_void;
+use std::ffi::{OsString, c_void};
+use std::io;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
 
+use crate::PathUnavailable;
 use crate::platform::macos::c_libproc::proc_pidpath;
 use crate::platform::macos::proc_pid::ProcPid;
 use crate::platform::macos::statics::PROC_PID_PATH_INFO_MAXSIZE;
 
-#[derive(Default, Clone)]
-pub(super) struct ProcPath(pub(super) String);
+#[derive(Clone)]
+pub(super) struct ProcPath(pub(super) Result<PathBuf, PathUnavailable>);
 
 impl ProcPath {
-    fn new(path: String) -> Self {
-        ProcPath(path)
+    fn new(path: PathBuf) -> Self {
+        ProcPath(Ok(path))
     }
 
     pub(super) fn from_pid(pid: ProcPid) -> Self {
+        // the kernel (`kernel_task`) has no executable
+        if pid.as_c_int() == 0 {
+            return Self(Err(PathUnavailable::KernelThread));
+        }
+
         let mut buf: Vec<u8> = Vec::with_capacity(PROC_PID_PATH_INFO_MAXSIZE);
         let buffer_ptr = buf.as_mut_ptr().cast::<c_void>();
         let buffer_size = u32::try_from(buf.capacity()).unwrap_or(4096);
@@ -25,17 +33,15 @@ impl ProcPath {
         };
 
         if ret <= 0 {
-            return Self(String::new());
+            let error = io::Error::last_os_error();
+            return Self(Err(PathUnavailable::from_io_error(&error)));
         }
 
         unsafe {
             buf.set_len(usize::try_from(ret).unwrap_or_default());
         }
 
-        match String::from_utf8(buf) {
-            Ok(path) => Self::new(path),
-            Err(_) => Self(String::new()),
-        }
+        Self::new(PathBuf::from(OsString::from_vec(buf)))
     }
 }
 
@@ -51,10 +57,9 @@ impl ProcPathsCache {
     }
 
     pub(super) fn get(&mut self, pid: ProcPid) -> ProcPath {
-        if let Entry::Vacant(e) = self.cache.entry(pid) {
-            e.insert(ProcPath::from_pid(pid));
-        }
-
-        self.cache.get(&pid).cloned().unwrap_or_default()
+        self.cache
+            .entry(pid)
+            .or_insert_with(|| ProcPath::from_pid(pid))
+            .clone()
     }
 }
@@ -14,14 +14,22 @@ use crate::{Protocol, SocketState};
 #[derive(Debug)]
 pub(super) struct ProtoListener {
     local_addr: SocketAddr,
+    remote_addr: Option<SocketAddr>,
     protocol: Protocol,
     state: SocketState,
 }
 
 impl ProtoListener {
-    pub(super) fn new(addr: IpAddr, port: u16, protocol: Protocol, state: SocketState) -> Self {
+    pub(super) fn new(
+        addr: IpAddr,
+        port: u16,
+        remote_addr: Option<SocketAddr>,
+        protocol: Protocol,
+        state: SocketState,
+    ) -> Self {
         ProtoListener {
             local_addr: SocketAddr::new(addr, port),
+            remote_addr,
             protocol,
             state,
         }
@@ -31,6 +39,10 @@ impl ProtoListener {
         self.local_addr
     }
 
+    pub(super) fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote_addr
+    }
+
     pub(super) fn protocol(&self) -> Protocol {
         self.protocol
     }
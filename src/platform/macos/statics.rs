@@ -1,4 +1,5 @@
 use std::ffi::c_int;
+use std::ops::RangeInclusive;
 
 pub(super) const PROC_ALL_PIDS: u32 = 1;
 pub(super) const PROC_PID_LIST_FDS: c_int = 1;
@@ -8,3 +9,5 @@ pub(super) const FD_TYPE_SOCKET: u32 = 2;
 pub(super) const PROC_PID_PATH_INFO_MAXSIZE: usize = 4096;
 pub(super) const IPPROTO_TCP: c_int = 6;
 pub(super) const IPPROTO_UDP: c_int = 17;
+// default values of net.inet.ip.portrange.first and net.inet.ip.portrange.last
+pub(super) const EPHEMERAL_PORTS: RangeInclusive<u16> = 49152..=65535;
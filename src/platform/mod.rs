@@ -1,6 +1,23 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use crate::{Protocol, SocketState};
+
+pub(crate) use target_os::ephemeral_port_range;
 pub(crate) use target_os::get_all;
 pub(crate) use target_os::get_process_by_port;
 
+pub(crate) use extended::Poller;
+#[cfg(unix)]
+pub(crate) use extended::describe_fd;
+pub(crate) use extended::get_descendant_pids;
+pub(crate) use extended::get_listeners;
+pub(crate) use extended::get_own_listeners;
+pub(crate) use extended::get_process_info;
+pub(crate) use extended::get_processes_by_ports;
+pub(crate) use extended::get_unowned_sockets;
+pub(crate) use extended::parent_pid;
+
 /* ---------- windows ---------- */
 #[cfg(target_os = "windows")]
 mod windows;
@@ -18,6 +35,8 @@ use macos as target_os;
 mod linux;
 #[cfg(target_os = "linux")]
 use linux as target_os;
+#[cfg(target_os = "linux")]
+use linux as extended;
 
 /* ---------- bsd-like ---------- */
 
@@ -45,3 +64,77 @@ mod unsupported;
     not(target_os = "netbsd")
 ))]
 use unsupported as target_os;
+
+/* --------- fallback --------- */
+// operations beyond `get_all` are only implemented natively on Linux
+#[cfg(not(target_os = "linux"))]
+mod fallback;
+#[cfg(not(target_os = "linux"))]
+use fallback as extended;
+
+/// Returns the remote socket of a connection, or `None` if the socket isn't connected to any peer.
+#[cfg(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+pub(crate) fn remote_socket(ip: std::net::IpAddr, port: u16) -> Option<std::net::SocketAddr> {
+    if ip.is_unspecified() && port == 0 {
+        None
+    } else {
+        Some(std::net::SocketAddr::new(ip, port))
+    }
+}
+
+/// Picks the owner of each port among the owners of the sockets using it.
+///
+/// Owners of listening TCP sockets and unconnected UDP sockets are preferred
+/// over the ones of connections using the same port (e.g. held by a forked worker).
+pub(crate) fn owners_by_port<T>(
+    sockets: impl IntoIterator<Item = (u16, Protocol, SocketState, T)>,
+) -> HashMap<(u16, Protocol), T> {
+    let mut owners: HashMap<(u16, Protocol), (bool, T)> = HashMap::new();
+    for (port, protocol, state, owner) in sockets {
+        let receiving = matches!(state, SocketState::Listen | SocketState::Unconnected);
+        match owners.entry((port, protocol)) {
+            Entry::Occupied(mut entry) => {
+                if receiving && !entry.get().0 {
+                    entry.insert((receiving, owner));
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((receiving, owner));
+            }
+        }
+    }
+    owners
+        .into_iter()
+        .map(|(key, (_, owner))| (key, owner))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::owners_by_port;
+    use crate::{Protocol, SocketState};
+
+    #[test]
+    fn test_owners_by_port() {
+        let owners = owners_by_port([
+            (80, Protocol::TCP, SocketState::Established, "worker"),
+            (80, Protocol::TCP, SocketState::Listen, "server"),
+            (80, Protocol::TCP, SocketState::CloseWait, "other worker"),
+            (53, Protocol::UDP, SocketState::Connected, "client"),
+            (53, Protocol::UDP, SocketState::Unconnected, "resolver"),
+            (53, Protocol::UDP, SocketState::Connected, "other client"),
+            (53, Protocol::TCP, SocketState::Established, "connection"),
+        ]);
+        assert_eq!(owners.len(), 3);
+        assert_eq!(owners[&(80, Protocol::TCP)], "server");
+        assert_eq!(owners[&(53, Protocol::UDP)], "resolver");
+        assert_eq!(owners[&(53, Protocol::TCP)], "connection");
+    }
+}
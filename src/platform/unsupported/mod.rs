@@ -1,5 +1,6 @@
 use crate::{Listener, Process, Protocol};
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
 pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
     Err("This OS isn't supported yet".into())
@@ -8,3 +9,8 @@ pub(crate) fn get_all() -> crate::Result<HashSet<Listener>> {
 pub(crate) fn get_process_by_port(_port: u16, _protocol: Protocol) -> crate::Result<Process> {
     Err("This OS isn't supported yet".into())
 }
+
+pub(crate) fn ephemeral_port_range() -> RangeInclusive<u16> {
+    // IANA suggested range for dynamic ports
+    49152..=65535
+}
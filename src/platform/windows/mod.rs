@@ -1,6 +1,8 @@
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
 use proto_listener::{PidNamePathCache, ProtoListener, pname_ppath};
+use statics::EPHEMERAL_PORTS;
 
 use crate::{Listener, Process, Protocol};
 
@@ -30,6 +32,10 @@ pub(crate) fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Resul
     let proto_listener = ProtoListener::get_by_port(port, protocol)?;
     let pid = proto_listener.pid;
     pname_ppath(pid)
-        .map(|(pname, ppath)| Process::new(pid, pname, ppath))
+        .map(|(pname, ppath)| Process::with_exe(pid, pname, ppath))
         .ok_or_else(|| "Could not get process path".into())
 }
+
+pub(crate) fn ephemeral_port_range() -> RangeInclusive<u16> {
+    EPHEMERAL_PORTS
+}
@@ -1,4 +1,6 @@
 use crate::Listener;
+use crate::PathUnavailable;
+use crate::Process;
 use crate::Protocol;
 use crate::SocketState;
 use crate::platform::windows::socket_table::SocketTable;
@@ -6,12 +8,13 @@ use crate::platform::windows::tcp_table::TcpTable;
 use crate::platform::windows::tcp6_table::Tcp6Table;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::ffi::OsString;
 use std::mem::size_of;
 use std::mem::zeroed;
 use std::net::{IpAddr, SocketAddr};
 use std::os::windows::ffi::OsStringExt;
-use std::path::Path;
-use windows::Win32::Foundation::CloseHandle;
+use std::path::PathBuf;
+use windows::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER};
 use windows::Win32::System::Diagnostics::ToolHelp::{
     CreateToolhelp32Snapshot, PROCESSENTRY32, Process32First, Process32Next, TH32CS_SNAPPROCESS,
 };
@@ -28,6 +31,7 @@ use super::udp6_table::Udp6Table;
 pub(super) struct ProtoListener {
     local_addr: IpAddr,
     local_port: u16,
+    remote: Option<SocketAddr>,
     pub(super) pid: u32,
     protocol: Protocol,
     state: SocketState,
@@ -79,6 +83,7 @@ impl ProtoListener {
     pub(super) fn new(
         local_addr: IpAddr,
         local_port: u16,
+        remote: Option<SocketAddr>,
         pid: u32,
         protocol: Protocol,
         state: SocketState,
@@ -86,6 +91,7 @@ impl ProtoListener {
         Self {
             local_addr,
             local_port,
+            remote,
             pid,
             protocol,
             state,
@@ -93,24 +99,24 @@ impl ProtoListener {
     }
 }
 
-pub(super) fn pname_ppath(pid: u32) -> Option<(String, String)> {
-    let path_str = ppath(pid);
+pub(super) fn pname_ppath(pid: u32) -> Option<(String, Result<PathBuf, PathUnavailable>)> {
+    let exe = ppath(pid);
 
-    let path = Path::new(&path_str);
-    if path.is_file()
+    if let Ok(path) = &exe
+        && path.is_file()
         && let Some(name) = path.file_name()
         && !name.is_empty()
     {
         let name_str = name.to_string_lossy().into_owned();
-        return Some((name_str, path_str));
+        return Some((name_str, exe));
     }
 
     let pname = pname(pid);
-    pname.zip(Some(path_str))
+    pname.zip(Some(exe))
 }
 
 pub(super) struct PidNamePathCache {
-    cache: HashMap<u32, Option<(String, String)>>,
+    cache: HashMap<u32, Option<(String, Result<PathBuf, PathUnavailable>)>>,
     names: HashMap<u32, String>,
 }
 
@@ -137,11 +143,10 @@ impl PidNamePathCache {
             .flatten()
             .map(|(pname, ppath)| {
                 let socket = SocketAddr::new(proto_listener.local_addr, proto_listener.local_port);
-                Listener::new(
-                    pid,
-                    pname,
-                    ppath,
+                Listener::with_process(
+                    Process::with_exe(pid, pname, ppath),
                     socket,
+                    proto_listener.remote,
                     proto_listener.protocol,
                     proto_listener.state,
                 )
@@ -182,13 +187,17 @@ fn pname(pid: u32) -> Option<String> {
     result
 }
 
-fn ppath(pid: u32) -> String {
+fn ppath(pid: u32) -> Result<PathBuf, PathUnavailable> {
+    // the System Idle Process and the System process run kernel code only
+    if pid == 0 || pid == 4 {
+        return Err(PathUnavailable::KernelThread);
+    }
+
     unsafe {
-        let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
-            return String::new();
-        };
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+            .map_err(|e| path_unavailable(&e))?;
         if handle.is_invalid() {
-            return String::new();
+            return Err(PathUnavailable::Unknown);
         }
 
         let mut buffer: [u16; 1024] = [0; 1024];
@@ -202,12 +211,22 @@ fn ppath(pid: u32) -> String {
         );
         let _ = CloseHandle(handle);
 
-        if result.is_err() {
-            return String::new();
-        }
+        result.map_err(|e| path_unavailable(&e))?;
+
+        Ok(PathBuf::from(OsString::from_wide(&buffer[..size as usize])))
+    }
+}
 
-        let path = std::ffi::OsString::from_wide(&buffer[..size as usize]);
-        path.to_string_lossy().into_owned()
+/// Classifies the error returned when opening a process or querying its image name.
+fn path_unavailable(error: &windows::core::Error) -> PathUnavailable {
+    let code = error.code();
+    if code == ERROR_ACCESS_DENIED.to_hresult() {
+        PathUnavailable::PermissionDenied
+    } else if code == ERROR_INVALID_PARAMETER.to_hresult() {
+        // `OpenProcess` fails with this error when no process has the given PID
+        PathUnavailable::ProcessExited
+    } else {
+        PathUnavailable::Unknown
     }
 }
 
@@ -4,6 +4,7 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use super::statics::UDP_TABLE_OWNER_PID;
 use crate::Protocol;
 use crate::SocketState;
+use crate::platform::remote_socket;
 use crate::platform::target_os::proto_listener::ProtoListener;
 use crate::platform::windows::statics::{
     AF_INET, AF_INET6, ERROR_INSUFFICIENT_BUFFER, NO_ERROR, TCP_TABLE_OWNER_PID_ALL,
@@ -52,12 +53,23 @@ impl SocketTable for TcpTable {
             return None;
         }
 
+        // the remote address and port are undefined for sockets in LISTEN state
+        let state = SocketState::from_windows(row.state);
+        let remote = if state == SocketState::Listen {
+            None
+        } else {
+            let remote_port = u16::from_be(u16::try_from(row.remote_port).ok()?);
+            let remote_addr = IpAddr::V4(Ipv4Addr::from(u32::from_be(row.remote_addr)));
+            remote_socket(remote_addr, remote_port)
+        };
+
         Some(ProtoListener::new(
             IpAddr::V4(Ipv4Addr::from(u32::from_be(row.local_addr))),
             port,
+            remote,
             row.owning_pid,
             Protocol::TCP,
-            SocketState::from_windows(row.state),
+            state,
         ))
     }
 }
@@ -90,12 +102,22 @@ impl SocketTable for Tcp6Table {
             return None;
         }
 
+        // the remote address and port are undefined for sockets in LISTEN state
+        let state = SocketState::from_windows(row.state);
+        let remote = if state == SocketState::Listen {
+            None
+        } else {
+            let remote_port = u16::from_be(u16::try_from(row.remote_port).ok()?);
+            remote_socket(IpAddr::V6(Ipv6Addr::from(row.remote_addr)), remote_port)
+        };
+
         Some(ProtoListener::new(
             IpAddr::V6(Ipv6Addr::from(row.local_addr)),
             port,
+            remote,
             row.owning_pid,
             Protocol::TCP,
-            SocketState::from_windows(row.state),
+            state,
         ))
     }
 }
@@ -131,6 +153,7 @@ impl SocketTable for UdpTable {
         Some(ProtoListener::new(
             IpAddr::V4(Ipv4Addr::from(u32::from_be(row.local_addr))),
             port,
+            None,
             row.owning_pid,
             Protocol::UDP,
             SocketState::Unknown,
@@ -169,6 +192,7 @@ impl SocketTable for Udp6Table {
         Some(ProtoListener::new(
             IpAddr::V6(Ipv6Addr::from(row.local_addr)),
             port,
+            None,
             row.owning_pid,
             Protocol::UDP,
             SocketState::Unknown,
@@ -1,4 +1,5 @@
 use std::ffi::c_ulong;
+use std::ops::RangeInclusive;
 use windows::Win32::NetworkManagement::IpHelper::{TCP_TABLE_CLASS, UDP_TABLE_CLASS};
 
 pub(super) const TCP_TABLE_OWNER_PID_ALL: TCP_TABLE_CLASS = TCP_TABLE_CLASS(5);
@@ -7,3 +8,5 @@ pub(super) const ERROR_INSUFFICIENT_BUFFER: c_ulong = 0x7A;
 pub(super) const NO_ERROR: c_ulong = 0;
 pub(super) const AF_INET: c_ulong = 2;
 pub(super) const AF_INET6: c_ulong = 23;
+// default dynamic port range (netsh int ipv4 show dynamicport tcp)
+pub(super) const EPHEMERAL_PORTS: RangeInclusive<u16> = 49152..=65535;
@@ -14,9 +14,9 @@ pub(super) struct Tcp6Row {
     pub(super) local_addr: [c_uchar; 16],
     local_scope_id: c_ulong,
     pub(super) local_port: c_ulong,
-    remote_addr: [c_uchar; 16],
+    pub(super) remote_addr: [c_uchar; 16],
     remote_scope_id: c_ulong,
-    remote_port: c_ulong,
+    pub(super) remote_port: c_ulong,
     pub(super) state: c_ulong,
     pub(super) owning_pid: c_ulong,
 }
@@ -13,7 +13,7 @@ pub(super) struct TcpRow {
     pub(super) state: c_ulong,
     pub(super) local_addr: c_ulong,
     pub(super) local_port: c_ulong,
-    remote_addr: c_ulong,
-    remote_port: c_ulong,
+    pub(super) remote_addr: c_ulong,
+    pub(super) remote_port: c_ulong,
     pub(super) owning_pid: c_ulong,
 }
@@ -0,0 +1,91 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::{Process, platform};
+
+/// Extended information about a [Process], loaded on demand via [`Process::info`].
+///
+/// Fields are `None` when they can't be read (e.g. due to insufficient permissions).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessInfo {
+    /// Command line arguments, starting with the program name (empty for kernel threads).
+    ///
+    /// Arguments are kept as they are, even if not valid UTF-8; with the `serde` feature,
+    /// they're (de)serialized as strings, so invalid sequences are replaced with `U+FFFD`.
+    #[cfg_attr(feature = "serde", serde(with = "serde_cmdline"))]
+    pub cmdline: Vec<OsString>,
+    /// Parent process ID.
+    pub parent_pid: Option<u32>,
+    /// Real user ID.
+    pub uid: Option<u32>,
+    /// Effective user ID.
+    pub effective_uid: Option<u32>,
+    /// Real group ID.
+    pub gid: Option<u32>,
+    /// Effective group ID.
+    pub effective_gid: Option<u32>,
+    /// Time the process was started at.
+    pub start_time: Option<SystemTime>,
+    /// Current working directory.
+    pub cwd: Option<PathBuf>,
+    /// Session ID.
+    pub session: Option<u32>,
+    /// Device number of the controlling terminal, or `None` if the process has no terminal.
+    pub tty: Option<u64>,
+}
+
+impl Process {
+    /// Loads extended information about this process (command line, parent, owner, start time, ...).
+    ///
+    /// Information is read from the system at every call: if the process exited in the meantime, an error is returned.
+    /// Only available on Linux, where it's read from `/proc/<pid>/{cmdline,status,stat,cwd}`;
+    /// the process is identified by its [start time](Process::start_time) too,
+    /// so that another process reusing the same PID isn't described instead.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the process doesn't exist anymore (even if its PID was reused),
+    /// or if extended information isn't supported on the current platform.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Ok(process) = listeners::get_process_by_port(3306, listeners::Protocol::TCP)
+    ///     && let Ok(info) = process.info()
+    /// {
+    ///     println!("{process} Command line: {}", info.cmdline.join(" ".as_ref()).display());
+    /// }
+    /// ```
+    pub fn info(&self) -> crate::Result<ProcessInfo> {
+        platform::get_process_info(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_cmdline {
+    use std::ffi::OsString;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        cmdline: &[OsString],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        cmdline
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<OsString>, D::Error> {
+        Ok(Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(OsString::from)
+            .collect())
+    }
+}
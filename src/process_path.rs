@@ -0,0 +1,112 @@
+use std::fmt::Display;
+use std::io;
+
+/// `ESRCH` ("no such process"), which has the same value on every supported Unix platform.
+#[cfg(unix)]
+const ESRCH: i32 = 3;
+
+/// The reason why the executable path of a [`Process`](crate::Process) is unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum PathUnavailable {
+    /// The current user isn't allowed to inspect the process (e.g. a process of another user).
+    PermissionDenied,
+    /// The process is a kernel thread (or the Windows `System` and `Idle` processes), without an executable.
+    KernelThread,
+    /// The process exited, or is a zombie, before its path could be read.
+    ProcessExited,
+    /// The platform doesn't expose the executable path of processes (OpenBSD).
+    Unsupported,
+    /// The path couldn't be read for another reason.
+    Unknown,
+}
+
+impl PathUnavailable {
+    /// Classifies the error returned when reading the executable path of a process.
+    pub(crate) fn from_io_error(error: &io::Error) -> Self {
+        #[cfg(unix)]
+        if error.raw_os_error() == Some(ESRCH) {
+            return PathUnavailable::ProcessExited;
+        }
+        match error.kind() {
+            io::ErrorKind::PermissionDenied => PathUnavailable::PermissionDenied,
+            io::ErrorKind::NotFound => PathUnavailable::ProcessExited,
+            _ => PathUnavailable::Unknown,
+        }
+    }
+}
+
+impl Display for PathUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            PathUnavailable::PermissionDenied => "permission denied",
+            PathUnavailable::KernelThread => "kernel thread without an executable",
+            PathUnavailable::ProcessExited => "process exited",
+            PathUnavailable::Unsupported => "not supported on this platform",
+            PathUnavailable::Unknown => "unknown reason",
+        };
+        write!(f, "Process path unavailable: {reason}")
+    }
+}
+
+impl std::error::Error for PathUnavailable {}
+
+/// (De)serializes [`Process::exe`](crate::Process::exe) as a `Result`,
+/// converting non-UTF-8 paths lossily since most formats only support UTF-8 strings.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_exe {
+    use std::path::PathBuf;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::PathUnavailable;
+
+    pub(crate) fn serialize<S: Serializer>(
+        exe: &Result<PathBuf, PathUnavailable>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        exe.as_ref()
+            .map(|path| path.to_string_lossy())
+            .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Result<PathBuf, PathUnavailable>, D::Error> {
+        Ok(Result::<String, PathUnavailable>::deserialize(deserializer)?.map(PathBuf::from))
+    }
+
+    /// The value of data serialized before the field was introduced.
+    pub(crate) fn default() -> Result<PathBuf, PathUnavailable> {
+        Err(PathUnavailable::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::PathUnavailable;
+
+    #[test]
+    fn test_path_unavailable_from_io_error() {
+        assert_eq!(
+            PathUnavailable::from_io_error(&io::Error::from(io::ErrorKind::PermissionDenied)),
+            PathUnavailable::PermissionDenied
+        );
+        assert_eq!(
+            PathUnavailable::from_io_error(&io::Error::from(io::ErrorKind::NotFound)),
+            PathUnavailable::ProcessExited
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            PathUnavailable::from_io_error(&io::Error::from_raw_os_error(3)),
+            PathUnavailable::ProcessExited
+        );
+        assert_eq!(
+            PathUnavailable::from_io_error(&io::Error::other("test")),
+            PathUnavailable::Unknown
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+
+use crate::filter::glob_match;
+use crate::{Listener, Process, Protocol, SocketState, platform};
+
+/// The address family of a socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressFamily {
+    /// Internet Protocol version 4.
+    #[cfg_attr(feature = "serde", serde(rename = "IPV4"))]
+    IPv4,
+    /// Internet Protocol version 6.
+    #[cfg_attr(feature = "serde", serde(rename = "IPV6"))]
+    IPv6,
+}
+
+/// A builder to retrieve the [Listener]s matching some criteria.
+///
+/// Criteria of different kinds must all be satisfied, while multiple values of the same kind are alternatives
+/// (e.g. `.protocol(Protocol::TCP).state(SocketState::Listen).state(SocketState::Established)`
+/// matches TCP sockets either listening or established).
+///
+/// Filters are applied as early as possible by the platform backend:
+/// on Linux, socket tables of excluded protocols and address families aren't read,
+/// and only the processes that may own a matching socket are inspected.
+/// On the other platforms, listeners are currently filtered after being retrieved.
+///
+/// # Example
+///
+/// ```
+/// use listeners::{ListenerQuery, Protocol, SocketState};
+///
+/// let query = ListenerQuery::new()
+///     .protocol(Protocol::TCP)
+///     .state(SocketState::Listen)
+///     .port_range(8000..=8999);
+/// if let Ok(listeners) = query.execute() {
+///     for l in listeners {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ListenerQuery {
+    pub(crate) protocols: Vec<Protocol>,
+    pub(crate) families: Vec<AddressFamily>,
+    pub(crate) states: Vec<SocketState>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_port_ranges"))]
+    pub(crate) ports: Vec<RangeInclusive<u16>>,
+    pub(crate) pids: Vec<u32>,
+    pub(crate) local_addrs: Vec<IpAddr>,
+    pub(crate) processes: Vec<String>,
+}
+
+impl ListenerQuery {
+    /// Creates a query matching all the listeners.
+    pub fn new() -> Self {
+        ListenerQuery::default()
+    }
+
+    /// Only matches sockets using the given protocol.
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.protocols.push(protocol);
+        self
+    }
+
+    /// Only matches sockets of the given address family.
+    pub fn family(mut self, family: AddressFamily) -> Self {
+        self.families.push(family);
+        self
+    }
+
+    /// Only matches sockets in the given state.
+    pub fn state(mut self, state: SocketState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Only matches sockets bound to the given local port.
+    pub fn port(self, port: u16) -> Self {
+        self.port_range(port..=port)
+    }
+
+    /// Only matches sockets bound to a local port in the given range.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the range is reversed (i.e. its start is greater than its end).
+    pub fn port_range(mut self, ports: RangeInclusive<u16>) -> Self {
+        assert!(
+            ports.start() <= ports.end(),
+            "Invalid port range: {}-{}",
+            ports.start(),
+            ports.end()
+        );
+        self.ports.push(ports);
+        self
+    }
+
+    /// Only matches sockets owned by the given process ID.
+    pub fn pid(mut self, pid: u32) -> Self {
+        self.pids.push(pid);
+        self
+    }
+
+    /// Only matches sockets bound to the given local IP address.
+    pub fn local_addr(mut self, addr: IpAddr) -> Self {
+        self.local_addrs.push(addr.to_canonical());
+        self
+    }
+
+    /// Only matches sockets owned by processes with the given name or executable path.
+    ///
+    /// A pattern containing a `/` is compared to the full executable path, otherwise to the process name.
+    /// Patterns may contain `*` (any sequence of characters) and `?` (any single character) wildcards.
+    pub fn process(mut self, name_or_path: &str) -> Self {
+        self.processes.push(name_or_path.to_string());
+        self
+    }
+
+    /// Returns whether the given [Listener] is matched by this query.
+    pub fn matches(&self, listener: &Listener) -> bool {
+        self.matches_socket(listener.protocol, listener.socket, listener.state)
+            && (self.pids.is_empty() || self.pids.contains(&listener.process.pid))
+            && self.matches_process(&listener.process)
+    }
+
+    /// Retrieves the [Listener]s matched by this query.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if it fails to retrieve listeners for the current platform.
+    pub fn execute(&self) -> crate::Result<HashSet<Listener>> {
+        platform::get_listeners(self)
+    }
+
+    /// Returns whether sockets of the given protocol and address family can be matched by this query.
+    pub(crate) fn includes(&self, protocol: Protocol, family: AddressFamily) -> bool {
+        (self.protocols.is_empty() || self.protocols.contains(&protocol))
+            && (self.families.is_empty() || self.families.contains(&family))
+    }
+
+    /// Returns whether the given process is matched by the name and path patterns of this query.
+    pub(crate) fn matches_process(&self, process: &Process) -> bool {
+        self.processes.is_empty()
+            || self.processes.iter().any(|pattern| {
+                match_process_pattern(
+                    pattern,
+                    &process.comm,
+                    || process.name.clone(),
+                    || process.path.clone(),
+                )
+            })
+    }
+
+    /// Returns whether a socket is matched by this query, regardless of its owning process.
+    pub(crate) fn matches_socket(
+        &self,
+        protocol: Protocol,
+        socket: SocketAddr,
+        state: SocketState,
+    ) -> bool {
+        let family = if socket.is_ipv4() {
+            AddressFamily::IPv4
+        } else {
+            AddressFamily::IPv6
+        };
+        self.includes(protocol, family)
+            && (self.states.is_empty() || self.states.contains(&state))
+            && (self.ports.is_empty()
+                || self
+                    .ports
+                    .iter()
+                    .any(|ports| ports.contains(&socket.port())))
+            && (self.local_addrs.is_empty()
+                || self.local_addrs.contains(&socket.ip().to_canonical()))
+    }
+}
+
+/// Matches a process name or executable path pattern, only retrieving the name and path if needed.
+///
+/// A name pattern matches either the name reported by the kernel (possibly truncated) or the full name.
+pub(crate) fn match_process_pattern(
+    pattern: &str,
+    comm: &str,
+    name: impl FnOnce() -> String,
+    path: impl FnOnce() -> String,
+) -> bool {
+    if pattern.contains('/') {
+        glob_match(pattern, &path())
+    } else {
+        glob_match(pattern, comm) || glob_match(pattern, &name())
+    }
+}
+
+/// Deserializes the port ranges of a query, rejecting reversed ranges like [`ListenerQuery::port_range`] does.
+#[cfg(feature = "serde")]
+fn deserialize_port_ranges<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<RangeInclusive<u16>>, D::Error> {
+    let ports = <Vec<RangeInclusive<u16>> as serde::Deserialize>::deserialize(deserializer)?;
+    if let Some(reversed) = ports.iter().find(|ports| ports.start() > ports.end()) {
+        return Err(serde::de::Error::custom(format!(
+            "Invalid port range: {}-{}",
+            reversed.start(),
+            reversed.end()
+        )));
+    }
+    Ok(ports)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use crate::query::{AddressFamily, ListenerQuery};
+    use crate::{Listener, Protocol, SocketState};
+
+    #[test]
+    fn test_matches() {
+        let tcp_v4 = Listener::fixture(1, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+        let tcp_v6 = Listener::fixture(2, "[::1]:443", Protocol::TCP, SocketState::Established);
+        let udp_v4 = Listener::fixture(3, "0.0.0.0:53", Protocol::UDP, SocketState::Unconnected);
+        let all = [&tcp_v4, &tcp_v6, &udp_v4];
+
+        let matching = |query: ListenerQuery| -> Vec<&Listener> {
+            all.iter().copied().filter(|l| query.matches(l)).collect()
+        };
+
+        assert_eq!(matching(ListenerQuery::new()), all);
+        assert_eq!(
+            matching(ListenerQuery::new().protocol(Protocol::TCP)),
+            [&tcp_v4, &tcp_v6]
+        );
+        assert_eq!(
+            matching(ListenerQuery::new().family(AddressFamily::IPv4)),
+            [&tcp_v4, &udp_v4]
+        );
+        assert_eq!(
+            matching(
+                ListenerQuery::new()
+                    .state(SocketState::Listen)
+                    .state(SocketState::Unconnected)
+            ),
+            [&tcp_v4, &udp_v4]
+        );
+        assert_eq!(matching(ListenerQuery::new().port(443)), [&tcp_v6]);
+        assert_eq!(
+            matching(ListenerQuery::new().port(8080).port(443)),
+            [&tcp_v4, &tcp_v6]
+        );
+        assert_eq!(
+            matching(ListenerQuery::new().port_range(1..=1023)),
+            [&tcp_v6, &udp_v4]
+        );
+        assert_eq!(matching(ListenerQuery::new().pid(3)), [&udp_v4]);
+        assert_eq!(
+            matching(ListenerQuery::new().local_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))),
+            [&tcp_v4]
+        );
+        assert_eq!(
+            matching(
+                ListenerQuery::new().local_addr(IpAddr::V6(Ipv4Addr::LOCALHOST.to_ipv6_mapped()))
+            ),
+            [&tcp_v4]
+        );
+        assert_eq!(
+            matching(ListenerQuery::new().local_addr(IpAddr::V6(Ipv6Addr::LOCALHOST))),
+            [&tcp_v6]
+        );
+        assert_eq!(matching(ListenerQuery::new().process("proc2")), [&tcp_v6]);
+        assert_eq!(
+            matching(ListenerQuery::new().process("proc?").port(53)),
+            [&udp_v4]
+        );
+        assert_eq!(
+            matching(ListenerQuery::new().process("path/to/proc1")),
+            [&tcp_v4]
+        );
+        assert_eq!(
+            matching(ListenerQuery::new().process("*/proc3").process("proc1")),
+            [&tcp_v4, &udp_v4]
+        );
+        assert!(matching(ListenerQuery::new().process("proc")).is_empty());
+        assert!(
+            matching(
+                ListenerQuery::new()
+                    .protocol(Protocol::UDP)
+                    .family(AddressFamily::IPv6)
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid port range: 9000-8000")]
+    fn test_reversed_port_range() {
+        let (start, end) = (9000, 8000);
+        let _ = ListenerQuery::new().port_range(start..=end);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let query = ListenerQuery::new()
+            .protocol(Protocol::TCP)
+            .port_range(8000..=8999)
+            .process("nginx");
+        let json = serde_json::to_string(&query).unwrap();
+        assert_eq!(serde_json::from_str::<ListenerQuery>(&json).unwrap(), query);
+        assert_eq!(
+            serde_json::to_string(&AddressFamily::IPv6).unwrap(),
+            "\"IPV6\""
+        );
+        // omitted criteria match everything
+        assert_eq!(
+            serde_json::from_str::<ListenerQuery>("{\"pids\":[1]}").unwrap(),
+            ListenerQuery::new().pid(1)
+        );
+        assert!(
+            serde_json::from_str::<ListenerQuery>("{\"ports\":[{\"start\":9000,\"end\":8000}]}")
+                .is_err()
+        );
+    }
+}
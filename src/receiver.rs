@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+use crate::{Listener, ListenerQuery, Protocol, SocketState};
+
+/// The sockets that would receive a packet or connection sent to a destination address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receiver {
+    /// The receiving sockets, sorted by PID.
+    ///
+    /// More than one socket means they form a `SO_REUSEPORT` group,
+    /// and the kernel picks one of them by hashing the addresses of each connection or datagram.
+    pub listeners: Vec<Listener>,
+    /// Whether the sockets are bound to exactly the destination address, rather than a wildcard one.
+    pub exact: bool,
+}
+
+impl Receiver {
+    /// Predicts which of the given [Listener]s would receive traffic sent to a destination address.
+    ///
+    /// The kernel's socket lookup rules are applied:
+    /// - only TCP sockets in `LISTEN` state and unconnected UDP sockets are considered
+    ///   (connected UDP sockets only receive datagrams from their peer);
+    /// - a socket bound to exactly the destination address beats a wildcard one (`0.0.0.0` or `[::]`);
+    /// - IPv4 traffic is also received by IPv6 sockets bound to `[::]` (dual-stack) or to the v4-mapped address,
+    ///   but IPv4 sockets are preferred;
+    /// - sockets sharing the best match form a `SO_REUSEPORT` group.
+    ///
+    /// Socket options that aren't exposed by the operating system tables (`IPV6_V6ONLY`, `SO_BINDTODEVICE`)
+    /// are not taken into account: every `[::]` socket is assumed to be dual-stack.
+    ///
+    /// # Arguments
+    ///
+    /// * `listeners` - The candidate listeners, usually obtained via [`crate::get_all`].
+    /// * `destination` - The destination address of the traffic.
+    /// * `protocol` - The protocol of the traffic.
+    ///
+    /// Returns `None` if no socket would receive the traffic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::net::SocketAddr;
+    ///
+    /// use listeners::{Protocol, Receiver};
+    ///
+    /// let destination = SocketAddr::from(([127, 0, 0, 1], 8080));
+    /// if let Ok(listeners) = listeners::get_all()
+    ///     && let Some(receiver) = Receiver::predict(&listeners, destination, Protocol::TCP)
+    /// {
+    ///     for l in receiver.listeners {
+    ///         println!("{l}");
+    ///     }
+    /// }
+    /// ```
+    pub fn predict(
+        listeners: &HashSet<Listener>,
+        destination: SocketAddr,
+        protocol: Protocol,
+    ) -> Option<Receiver> {
+        let scored: Vec<(u8, &Listener)> = listeners
+            .iter()
+            .filter_map(|l| score(l, destination, protocol).map(|score| (score, l)))
+            .collect();
+        let best = scored.iter().map(|(score, _)| *score).max()?;
+
+        let mut receivers: Vec<Listener> = scored
+            .into_iter()
+            .filter(|(score, _)| *score == best)
+            .map(|(_, l)| l.clone())
+            .collect();
+        receivers.sort_by_key(|l| (l.process.pid, l.socket));
+
+        Some(Receiver {
+            listeners: receivers,
+            exact: best >= EXACT,
+        })
+    }
+}
+
+/// Score of a socket bound to exactly the destination address.
+const EXACT: u8 = 2;
+
+/// Scores how a socket matches a destination address (the higher the better),
+/// or returns `None` if it wouldn't receive the traffic at all.
+fn score(listener: &Listener, destination: SocketAddr, protocol: Protocol) -> Option<u8> {
+    let receiving = match listener.protocol {
+        Protocol::TCP => listener.state == SocketState::Listen,
+        Protocol::UDP => listener.state != SocketState::Connected,
+    };
+    if listener.protocol != protocol || listener.socket.port() != destination.port() || !receiving {
+        return None;
+    }
+
+    let destination_ip = destination.ip().to_canonical();
+    let local_ip = listener.socket.ip();
+    let exact = if local_ip.to_canonical() == destination_ip {
+        true
+    } else if local_ip.is_unspecified() && (local_ip.is_ipv6() || destination_ip.is_ipv4()) {
+        // IPv6 sockets bound to `[::]` are dual-stack
+        false
+    } else {
+        return None;
+    };
+
+    Some(u8::from(exact) * EXACT + u8::from(local_ip.is_ipv4() && destination_ip.is_ipv4()))
+}
+
+/// Predicts which socket would receive traffic sent to a destination address.
+///
+/// Only the sockets bound to the destination port are retrieved; see [`Receiver::predict`] for the lookup rules.
+///
+/// # Arguments
+///
+/// * `destination` - The destination address of the traffic.
+/// * `protocol` - The protocol of the traffic.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+/// If no socket would receive the traffic, `None` is returned.
+///
+/// # Example
+///
+/// ```
+/// use std::net::SocketAddr;
+///
+/// use listeners::Protocol;
+///
+/// let destination = SocketAddr::from(([127, 0, 0, 1], 8080));
+/// if let Ok(Some(receiver)) = listeners::predict_receiver(destination, Protocol::TCP) {
+///     for l in receiver.listeners {
+///         println!("{l}");
+///     }
+/// }
+/// ```
+pub fn predict_receiver(
+    destination: SocketAddr,
+    protocol: Protocol,
+) -> crate::Result<Option<Receiver>> {
+    let listeners = ListenerQuery::new()
+        .protocol(protocol)
+        .port(destination.port())
+        .execute()?;
+    Ok(Receiver::predict(&listeners, destination, protocol))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    use crate::receiver::Receiver;
+    use crate::{Listener, Protocol, SocketState};
+
+    fn predict(listeners: &[&Listener], destination: &str, protocol: Protocol) -> Option<Receiver> {
+        let listeners: HashSet<Listener> = listeners.iter().copied().cloned().collect();
+        Receiver::predict(
+            &listeners,
+            SocketAddr::from_str(destination).unwrap(),
+            protocol,
+        )
+    }
+
+    fn receiver(listeners: &[&Listener], exact: bool) -> Option<Receiver> {
+        Some(Receiver {
+            listeners: listeners.iter().copied().cloned().collect(),
+            exact,
+        })
+    }
+
+    #[test]
+    fn test_predict_exact_beats_wildcard() {
+        let exact = Listener::fixture(1, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+        let wildcard = Listener::fixture(2, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let all = [&exact, &wildcard];
+
+        assert_eq!(
+            predict(&all, "127.0.0.1:8080", Protocol::TCP),
+            receiver(&[&exact], true)
+        );
+        assert_eq!(
+            predict(&all, "10.0.0.5:8080", Protocol::TCP),
+            receiver(&[&wildcard], false)
+        );
+        assert_eq!(predict(&all, "127.0.0.1:8081", Protocol::TCP), None);
+        assert_eq!(predict(&all, "127.0.0.1:8080", Protocol::UDP), None);
+        assert_eq!(predict(&all, "[::1]:8080", Protocol::TCP), None);
+    }
+
+    #[test]
+    fn test_predict_dual_stack() {
+        let v4_wildcard = Listener::fixture(1, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let v6_wildcard = Listener::fixture(2, "[::]:8080", Protocol::TCP, SocketState::Listen);
+        let v4_mapped = Listener::fixture(
+            3,
+            "[::ffff:127.0.0.1]:8080",
+            Protocol::TCP,
+            SocketState::Listen,
+        );
+        let v4_exact = Listener::fixture(4, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+
+        // dual-stack sockets receive IPv4 traffic, but IPv4 sockets are preferred
+        assert_eq!(
+            predict(&[&v6_wildcard], "127.0.0.1:8080", Protocol::TCP),
+            receiver(&[&v6_wildcard], false)
+        );
+        assert_eq!(
+            predict(
+                &[&v4_wildcard, &v6_wildcard],
+                "127.0.0.1:8080",
+                Protocol::TCP
+            ),
+            receiver(&[&v4_wildcard], false)
+        );
+        assert_eq!(
+            predict(&[&v4_wildcard, &v6_wildcard], "[::1]:8080", Protocol::TCP),
+            receiver(&[&v6_wildcard], false)
+        );
+        assert_eq!(
+            predict(&[&v4_wildcard, &v4_mapped], "127.0.0.1:8080", Protocol::TCP),
+            receiver(&[&v4_mapped], true)
+        );
+        assert_eq!(
+            predict(&[&v4_mapped, &v4_exact], "127.0.0.1:8080", Protocol::TCP),
+            receiver(&[&v4_exact], true)
+        );
+        assert_eq!(
+            predict(&[&v4_mapped], "[::ffff:127.0.0.1]:8080", Protocol::TCP),
+            receiver(&[&v4_mapped], true)
+        );
+    }
+
+    #[test]
+    fn test_predict_reuseport_group() {
+        let first = Listener::fixture(2, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let second = Listener::fixture(1, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let v6 = Listener::fixture(3, "[::]:8080", Protocol::TCP, SocketState::Listen);
+
+        assert_eq!(
+            predict(&[&first, &second, &v6], "127.0.0.1:8080", Protocol::TCP),
+            receiver(&[&second, &first], false)
+        );
+    }
+
+    #[test]
+    fn test_predict_receiving_states() {
+        let connection =
+            Listener::fixture(1, "127.0.0.1:8080", Protocol::TCP, SocketState::Established);
+        let udp_connected =
+            Listener::fixture(2, "127.0.0.1:53", Protocol::UDP, SocketState::Connected);
+        let udp_unconnected =
+            Listener::fixture(3, "0.0.0.0:53", Protocol::UDP, SocketState::Unconnected);
+        let udp_unknown = Listener::fixture(4, "[::]:53", Protocol::UDP, SocketState::Unknown);
+
+        assert_eq!(
+            predict(&[&connection], "127.0.0.1:8080", Protocol::TCP),
+            None
+        );
+        assert_eq!(
+            predict(
+                &[&udp_connected, &udp_unconnected],
+                "127.0.0.1:53",
+                Protocol::UDP
+            ),
+            receiver(&[&udp_unconnected], false)
+        );
+        assert_eq!(
+            predict(&[&udp_unknown], "[::1]:53", Protocol::UDP),
+            receiver(&[&udp_unknown], false)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::direction::accepts;
+use crate::{Listener, Protocol, SocketState};
+
+/// A TCP socket in `LISTEN` state, together with the connections accepted on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListeningService {
+    /// The listening socket.
+    pub listener: Listener,
+    /// The connections accepted on the listening socket.
+    pub connections: Vec<Listener>,
+}
+
+impl ListeningService {
+    /// Groups the given [Listener]s by listening socket.
+    ///
+    /// Every TCP socket in `LISTEN` state becomes a [`ListeningService`],
+    /// carrying the connections the same process accepted on it.
+    /// Connections are matched to the most specific listening socket of their process
+    /// (a socket bound to the exact local address is preferred over a wildcard one).
+    ///
+    /// Services are sorted by listening socket and PID, connections by remote socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `listeners` - The listeners to group, usually obtained via [`crate::get_all`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use listeners::ListeningService;
+    ///
+    /// if let Ok(listeners) = listeners::get_all() {
+    ///     for service in ListeningService::group(&listeners) {
+    ///         println!("{} -> {} clients", service.listener, service.connections.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn group(listeners: &HashSet<Listener>) -> Vec<ListeningService> {
+        let mut services: Vec<ListeningService> = listeners
+            .iter()
+            .filter(|l| l.protocol == Protocol::TCP && l.state == SocketState::Listen)
+            .map(|l| ListeningService {
+                listener: l.clone(),
+                connections: Vec::new(),
+            })
+            .collect();
+        services.sort_by_key(|s| (s.listener.socket, s.listener.process.pid));
+
+        for connection in listeners
+            .iter()
+            .filter(|l| l.protocol == Protocol::TCP && l.state != SocketState::Listen)
+        {
+            let best_service = services
+                .iter_mut()
+                .filter(|s| {
+                    s.listener.process.pid == connection.process.pid
+                        && accepts(&s.listener.socket, &connection.socket)
+                })
+                .max_by_key(|s| {
+                    let listen_ip = s.listener.socket.ip();
+                    (
+                        !listen_ip.is_unspecified(),
+                        listen_ip.is_ipv4() == connection.socket.ip().is_ipv4(),
+                    )
+                });
+            if let Some(service) = best_service {
+                service.connections.push(connection.clone());
+            }
+        }
+
+        for service in &mut services {
+            service
+                .connections
+                .sort_by_key(|c| (c.remote, c.socket, c.process.pid));
+        }
+
+        services
+    }
+
+    /// Returns the number of accepted connections in each [`SocketState`].
+    pub fn state_counts(&self) -> HashMap<SocketState, usize> {
+        let mut counts = HashMap::new();
+        for connection in &self.connections {
+            *counts.entry(connection.state).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::service::ListeningService;
+    use crate::{Listener, Protocol, SocketState};
+
+    #[test]
+    fn test_group() {
+        let listen_v4 = Listener::fixture(1, "0.0.0.0:5432", Protocol::TCP, SocketState::Listen);
+        let listen_v6 = Listener::fixture(1, "[::]:5432", Protocol::TCP, SocketState::Listen);
+        let listen_other =
+            Listener::fixture(2, "127.0.0.1:8080", Protocol::TCP, SocketState::Listen);
+        let conn_a =
+            Listener::fixture(1, "127.0.0.1:5432", Protocol::TCP, SocketState::Established)
+                .connected_to("127.0.0.1:40000");
+        let conn_b = Listener::fixture(1, "10.0.0.1:5432", Protocol::TCP, SocketState::CloseWait)
+            .connected_to("10.0.0.2:40001");
+        let conn_v6 = Listener::fixture(1, "[::1]:5432", Protocol::TCP, SocketState::Established)
+            .connected_to("[::1]:40002");
+        // outbound connection of the same process, not accepted on any listening socket
+        let outbound = Listener::fixture(
+            1,
+            "127.0.0.1:40003",
+            Protocol::TCP,
+            SocketState::Established,
+        )
+        .connected_to("127.0.0.1:8080");
+
+        let listeners = HashSet::from([
+            listen_v4.clone(),
+            listen_v6.clone(),
+            listen_other.clone(),
+            conn_a.clone(),
+            conn_b.clone(),
+            conn_v6.clone(),
+            outbound,
+        ]);
+        let services = ListeningService::group(&listeners);
+
+        assert_eq!(services.len(), 3);
+        assert_eq!(services[0].listener, listen_v4);
+        assert_eq!(
+            services[0].connections,
+            vec![conn_b.clone(), conn_a.clone()]
+        );
+        assert_eq!(services[1].listener, listen_other);
+        assert!(services[1].connections.is_empty());
+        assert_eq!(services[2].listener, listen_v6);
+        assert_eq!(services[2].connections, vec![conn_v6]);
+
+        let counts = services[0].state_counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&SocketState::Established], 1);
+        assert_eq!(counts[&SocketState::CloseWait], 1);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::time::SystemTime;
+
+use crate::{Listener, Protocol, SocketState};
+
+/// A timestamped set of [Listener]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// When the snapshot was taken.
+    pub taken_at: SystemTime,
+    /// The listeners at the time the snapshot was taken.
+    pub listeners: HashSet<Listener>,
+}
+
+/// How sockets are identified when comparing two [Snapshot]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum DiffMode {
+    /// Sockets are identified by PID (and process start time, where available), protocol, local socket, and remote socket.
+    #[default]
+    Exact,
+    /// Sockets are identified by process name, protocol, local port,
+    /// and whether they're bound to all the interfaces (`0.0.0.0` or `[::]`) or to a specific address.
+    ///
+    /// Only listening TCP sockets and unconnected UDP sockets are compared,
+    /// since connections and host-specific addresses differ between hosts anyway.
+    /// Useful to compare snapshots taken on different hosts (e.g. to find configuration drift),
+    /// or across process restarts.
+    PidAgnostic,
+}
+
+/// The differences between two [Snapshot]s.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotDiff {
+    /// Sockets only present in the newer snapshot.
+    pub opened: Vec<Listener>,
+    /// Sockets only present in the older snapshot.
+    pub closed: Vec<Listener>,
+    /// Sockets present in both snapshots, but in a different state.
+    pub state_changed: Vec<StateChange>,
+}
+
+/// A socket whose state changed between two [Snapshot]s.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateChange {
+    /// The socket in the older snapshot.
+    pub before: Listener,
+    /// The socket in the newer snapshot.
+    pub after: Listener,
+}
+
+impl Snapshot {
+    /// Takes a snapshot of all the [Listener]s.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if it fails to retrieve listeners for the current platform.
+    pub fn take() -> crate::Result<Snapshot> {
+        Ok(Snapshot::new(crate::get_all()?))
+    }
+
+    /// Creates a snapshot from the given [Listener]s, timestamped with the current time.
+    pub fn new(listeners: HashSet<Listener>) -> Snapshot {
+        Snapshot {
+            taken_at: SystemTime::now(),
+            listeners,
+        }
+    }
+
+    /// Compares this snapshot with a newer one, identifying sockets with [`DiffMode::Exact`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use listeners::Snapshot;
+    ///
+    /// if let (Ok(before), Ok(after)) = (Snapshot::take(), Snapshot::take()) {
+    ///     let diff = before.diff(&after);
+    ///     for l in diff.opened {
+    ///         println!("Opened: {l}");
+    ///     }
+    /// }
+    /// ```
+    pub fn diff(&self, newer: &Snapshot) -> SnapshotDiff {
+        self.diff_with(newer, DiffMode::Exact)
+    }
+
+    /// Compares this snapshot with a newer one, identifying sockets as specified by `mode`.
+    ///
+    /// The state of a socket isn't part of its identity:
+    /// a socket present in both snapshots with a different state is reported in [`SnapshotDiff::state_changed`].
+    pub fn diff_with(&self, newer: &Snapshot, mode: DiffMode) -> SnapshotDiff {
+        let older_by_key = group_by_key(&self.listeners, mode);
+        let mut newer_by_key = group_by_key(&newer.listeners, mode);
+
+        let mut diff = SnapshotDiff::default();
+
+        for (key, mut before) in older_by_key {
+            let mut after = newer_by_key.remove(&key).unwrap_or_default();
+
+            // sockets in the same state on both sides are unchanged
+            before.retain(|b| {
+                if let Some(i) = after.iter().position(|a| a.state == b.state) {
+                    after.swap_remove(i);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            let changed = before.len().min(after.len());
+            for (b, a) in before.drain(..changed).zip(after.drain(..changed)) {
+                diff.state_changed.push(StateChange {
+                    before: b.clone(),
+                    after: a.clone(),
+                });
+            }
+            diff.closed.extend(before.into_iter().cloned());
+            diff.opened.extend(after.into_iter().cloned());
+        }
+
+        for after in newer_by_key.into_values() {
+            diff.opened.extend(after.into_iter().cloned());
+        }
+
+        diff.opened.sort_by_key(sort_key);
+        diff.closed.sort_by_key(sort_key);
+        diff.state_changed.sort_by_key(|c| sort_key(&c.after));
+
+        diff
+    }
+}
+
+impl SnapshotDiff {
+    /// Whether the two compared snapshots are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty() && self.state_changed.is_empty()
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Owner<'a> {
+    Process(u32, Option<SystemTime>),
+    Name(&'a str),
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Local {
+    Socket(SocketAddr),
+    /// The port, and the wildcard address if the socket is bound to all the interfaces.
+    Port(u16, Option<IpAddr>),
+}
+
+type SocketKey<'a> = (Owner<'a>, Protocol, Local, Option<SocketAddr>);
+
+fn group_by_key(
+    listeners: &HashSet<Listener>,
+    mode: DiffMode,
+) -> HashMap<SocketKey<'_>, Vec<&Listener>> {
+    let mut map: HashMap<SocketKey, Vec<&Listener>> = HashMap::new();
+    for l in listeners {
+        let key = match mode {
+            DiffMode::Exact => (
+                Owner::Process(l.process.pid, l.process.start_time),
+                l.protocol,
+                Local::Socket(l.socket),
+                l.remote,
+            ),
+            DiffMode::PidAgnostic => {
+                if !matches!(l.state, SocketState::Listen | SocketState::Unconnected) {
+                    continue;
+                }
+                let ip = l.socket.ip();
+                (
+                    Owner::Name(&l.process.name),
+                    l.protocol,
+                    Local::Port(l.socket.port(), ip.is_unspecified().then_some(ip)),
+                    None,
+                )
+            }
+        };
+        map.entry(key).or_default().push(l);
+    }
+    map
+}
+
+fn sort_key(l: &Listener) -> (SocketAddr, Option<SocketAddr>, bool, u32) {
+    (
+        l.socket,
+        l.remote,
+        l.protocol == Protocol::UDP,
+        l.process.pid,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::snapshot::{DiffMode, Snapshot};
+    use crate::{Listener, Protocol, SocketState};
+
+    #[test]
+    fn test_diff() {
+        let kept =
+            Listener::fixture(1, "0.0.0.0:22", Protocol::TCP, SocketState::Listen).owned_by("sshd");
+        let closed = Listener::fixture(2, "0.0.0.0:80", Protocol::TCP, SocketState::Listen)
+            .owned_by("nginx");
+        let opened = Listener::fixture(3, "0.0.0.0:3000", Protocol::TCP, SocketState::Listen)
+            .owned_by("node");
+        let before_change =
+            Listener::fixture(4, "10.0.0.1:40000", Protocol::TCP, SocketState::Established)
+                .owned_by("curl");
+        let after_change =
+            Listener::fixture(4, "10.0.0.1:40000", Protocol::TCP, SocketState::TimeWait)
+                .owned_by("curl");
+
+        let older = Snapshot::new(HashSet::from([
+            kept.clone(),
+            closed.clone(),
+            before_change.clone(),
+        ]));
+        let newer = Snapshot::new(HashSet::from([
+            kept.clone(),
+            opened.clone(),
+            after_change.clone(),
+        ]));
+
+        let diff = older.diff(&newer);
+        assert_eq!(diff.opened, vec![opened.clone()]);
+        assert_eq!(diff.closed, vec![closed.clone()]);
+        assert_eq!(diff.state_changed.len(), 1);
+        assert_eq!(diff.state_changed[0].before, before_change);
+        assert_eq!(diff.state_changed[0].after, after_change);
+
+        // the reverse diff swaps opened and closed sockets
+        let reverse = newer.diff(&older);
+        assert_eq!(reverse.opened, vec![closed]);
+        assert_eq!(reverse.closed, vec![opened]);
+
+        assert!(older.diff(&older).is_empty());
+    }
+
+    #[test]
+    fn test_diff_pid_agnostic() {
+        let older = Snapshot::new(HashSet::from([
+            Listener::fixture(1, "0.0.0.0:22", Protocol::TCP, SocketState::Listen).owned_by("sshd"),
+            Listener::fixture(2, "0.0.0.0:80", Protocol::TCP, SocketState::Listen)
+                .owned_by("nginx"),
+        ]));
+        let newer = Snapshot::new(HashSet::from([
+            Listener::fixture(11, "0.0.0.0:22", Protocol::TCP, SocketState::Listen)
+                .owned_by("sshd"),
+            Listener::fixture(12, "0.0.0.0:80", Protocol::TCP, SocketState::Listen)
+                .owned_by("apache2"),
+        ]));
+
+        let exact = older.diff_with(&newer, DiffMode::Exact);
+        assert_eq!(exact.opened.len(), 2);
+        assert_eq!(exact.closed.len(), 2);
+
+        let pid_agnostic = older.diff_with(&newer, DiffMode::PidAgnostic);
+        assert_eq!(pid_agnostic.opened.len(), 1);
+        assert_eq!(pid_agnostic.opened[0].process.name, "apache2");
+        assert_eq!(pid_agnostic.closed.len(), 1);
+        assert_eq!(pid_agnostic.closed[0].process.name, "nginx");
+        assert!(pid_agnostic.state_changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_pid_agnostic_hosts() {
+        let host_a = Snapshot::new(HashSet::from([
+            Listener::fixture(1, "0.0.0.0:22", Protocol::TCP, SocketState::Listen).owned_by("sshd"),
+            Listener::fixture(2, "10.0.0.1:5432", Protocol::TCP, SocketState::Listen)
+                .owned_by("postgres"),
+            Listener::fixture(2, "10.0.0.1:5432", Protocol::TCP, SocketState::Established)
+                .owned_by("postgres")
+                .connected_to("10.0.0.9:40000"),
+            Listener::fixture(3, "10.0.0.1:41000", Protocol::TCP, SocketState::Established)
+                .owned_by("curl")
+                .connected_to("1.1.1.1:443"),
+        ]));
+        let host_b = Snapshot::new(HashSet::from([
+            Listener::fixture(7, "0.0.0.0:22", Protocol::TCP, SocketState::Listen).owned_by("sshd"),
+            Listener::fixture(8, "10.0.0.2:5432", Protocol::TCP, SocketState::Listen)
+                .owned_by("postgres"),
+            Listener::fixture(9, "10.0.0.2:53", Protocol::UDP, SocketState::Unconnected)
+                .owned_by("dnsmasq"),
+        ]));
+
+        let pid_agnostic = host_a.diff_with(&host_b, DiffMode::PidAgnostic);
+        assert_eq!(pid_agnostic.opened.len(), 1);
+        assert_eq!(pid_agnostic.opened[0].process.name, "dnsmasq");
+        assert!(pid_agnostic.closed.is_empty());
+        assert!(pid_agnostic.state_changed.is_empty());
+
+        // a service bound to all the interfaces on one host only is reported
+        let host_c = Snapshot::new(HashSet::from([
+            Listener::fixture(7, "0.0.0.0:22", Protocol::TCP, SocketState::Listen).owned_by("sshd"),
+            Listener::fixture(8, "0.0.0.0:5432", Protocol::TCP, SocketState::Listen)
+                .owned_by("postgres"),
+        ]));
+        let drift = host_a.diff_with(&host_c, DiffMode::PidAgnostic);
+        assert_eq!(drift.opened.len(), 1);
+        assert_eq!(drift.closed.len(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+//! Async APIs, available with the `tokio` feature.
+//!
+//! The platform backends are inherently blocking (on Linux they walk every `/proc/<pid>/fd`),
+//! so these functions run them on tokio's blocking thread pool, without stalling the async executor.
+//!
+//! They must be called from within a tokio runtime.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use ::tokio::time::{Interval, MissedTickBehavior};
+use futures_util::Stream;
+
+use crate::platform::Poller;
+use crate::{Listener, ListenerEvent, Process, Protocol, Snapshot};
+
+/// Async version of [`crate::get_all`].
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform.
+///
+/// # Example
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// if let Ok(listeners) = listeners::tokio::get_all().await {
+///     for l in listeners {
+///         println!("{l}");
+///     }
+/// }
+/// # }
+/// ```
+pub async fn get_all() -> crate::Result<HashSet<Listener>> {
+    spawn_blocking(crate::get_all).await
+}
+
+/// Async version of [`crate::get_process_by_port`].
+///
+/// # Arguments
+///
+/// * `port` - The port to look for.
+/// * `protocol` - The protocol to look for (TCP or UDP).
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve the process listening on the given port, or if no process is found.
+pub async fn get_process_by_port(port: u16, protocol: Protocol) -> crate::Result<Process> {
+    spawn_blocking(move || crate::get_process_by_port(port, protocol)).await
+}
+
+/// Async version of [`crate::watch`], returning a [`Stream`] of [`ListenerEvent`]s.
+///
+/// Listeners are polled at the given interval, and the stream yields an event for every socket
+/// opened, closed, or changing its state since the call to this function.
+///
+/// # Arguments
+///
+/// * `interval` - The time between two consecutive polls.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve the initial listeners for the current platform.
+pub async fn watch(
+    interval: Duration,
+) -> crate::Result<impl Stream<Item = crate::Result<ListenerEvent>>> {
+    let (poller, listeners) = poll(Poller::new()).await?;
+
+    let mut interval = ::tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // the first tick completes immediately
+    interval.tick().await;
+
+    let state = WatchState {
+        poller: Some(poller),
+        snapshot: Snapshot::new(listeners),
+        interval,
+        events: VecDeque::new(),
+    };
+
+    Ok(futures_util::stream::unfold(
+        state,
+        |mut state| async move {
+            loop {
+                if let Some(event) = state.events.pop_front() {
+                    return Some((Ok(event), state));
+                }
+
+                state.interval.tick().await;
+                // the poller is lost if polling fails, so its cache starts over
+                let poller = state.poller.take().unwrap_or_else(Poller::new);
+                match poll(poller).await {
+                    Ok((poller, listeners)) => {
+                        state.poller = Some(poller);
+                        let snapshot = Snapshot::new(listeners);
+                        let diff = state.snapshot.diff(&snapshot);
+                        state.events.extend(ListenerEvent::from_diff(diff));
+                        state.snapshot = snapshot;
+                    }
+                    Err(e) => return Some((Err(e), state)),
+                }
+            }
+        },
+    ))
+}
+
+struct WatchState {
+    poller: Option<Poller>,
+    snapshot: Snapshot,
+    interval: Interval,
+    events: VecDeque<ListenerEvent>,
+}
+
+async fn poll(mut poller: Poller) -> crate::Result<(Poller, HashSet<Listener>)> {
+    spawn_blocking(move || {
+        let listeners = poller.poll()?;
+        Ok((poller, listeners))
+    })
+    .await
+}
+
+/// Runs a blocking function on tokio's blocking thread pool.
+async fn spawn_blocking<T, F>(f: F) -> crate::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> crate::Result<T> + Send + 'static,
+{
+    // errors aren't `Send`, so they're sent back to the async context as strings
+    ::tokio::task::spawn_blocking(move || f().map_err(|e| e.to_string()))
+        .await?
+        .map_err(Into::into)
+}
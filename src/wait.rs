@@ -0,0 +1,178 @@
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::{
+    BindOptions, Listener, ListenerQuery, Protocol, SocketState, check_bind_with, platform,
+};
+
+/// How long to wait between two checks.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The maximum depth of the process tree walked to find out whether a process descends from another.
+const MAX_ANCESTORS: usize = 64;
+
+/// Waits until a process listens on the given port, and returns its [Listener].
+///
+/// A TCP socket is considered listening in `LISTEN` state, a UDP socket as soon as it's bound.
+/// If `pid` is specified, only sockets owned by that process or one of its descendants are accepted,
+/// so that a stale process holding the port isn't mistaken for the expected one
+/// (descendants are only recognized on Linux; on other platforms, the PID must match exactly).
+///
+/// # Arguments
+///
+/// * `port` - The port to wait for.
+/// * `protocol` - The protocol to wait for (TCP or UDP).
+/// * `pid` - The process expected to listen (e.g. a spawned child), or `None` to accept any process.
+/// * `timeout` - The maximum time to wait.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform,
+/// or if the timeout expires first (naming the processes holding the port, if any).
+///
+/// # Example
+///
+/// ```no_run
+/// use std::process::Command;
+/// use std::time::Duration;
+///
+/// use listeners::Protocol;
+///
+/// let child = Command::new("python3")
+///     .args(["-m", "http.server", "8000"])
+///     .spawn()
+///     .unwrap();
+/// let listener =
+///     listeners::wait_for_listener(8000, Protocol::TCP, Some(child.id()), Duration::from_secs(5))
+///         .unwrap();
+/// println!("Server ready: {listener}");
+/// ```
+pub fn wait_for_listener(
+    port: u16,
+    protocol: Protocol,
+    pid: Option<u32>,
+    timeout: Duration,
+) -> crate::Result<Listener> {
+    let deadline = Instant::now() + timeout;
+    let mut query = ListenerQuery::new().protocol(protocol).port(port);
+    if protocol == Protocol::TCP {
+        query = query.state(SocketState::Listen);
+    }
+
+    loop {
+        let mut listeners: Vec<Listener> = query.execute()?.into_iter().collect();
+        listeners.sort_by_key(|l| (l.process.pid, l.socket));
+        if let Some(listener) = listeners
+            .iter()
+            .find(|l| pid.is_none_or(|pid| is_same_or_descendant(l.process.pid, pid)))
+        {
+            return Ok(listener.clone());
+        }
+
+        if !sleep_until_next_poll(deadline) {
+            let mut error =
+                format!("Timed out waiting for a process listening on {protocol} port {port}");
+            if let Some(pid) = pid {
+                error.push_str(&format!(" (PID {pid} or one of its descendants)"));
+            }
+            if !listeners.is_empty() {
+                let holders: Vec<String> = listeners
+                    .iter()
+                    .map(|l| format!("PID {} ({})", l.process.pid, l.process.name))
+                    .collect();
+                error.push_str(&format!(", the port is held by {}", holders.join(", ")));
+            }
+            return Err(error.into());
+        }
+    }
+}
+
+/// Waits until the given port is free, i.e. until a socket could be bound to it on every address.
+///
+/// The port is checked with [`check_bind_with`] on the dual-stack `[::]` address,
+/// with the same socket options as [`crate::bind_tcp`] and [`crate::bind_udp`].
+///
+/// # Arguments
+///
+/// * `port` - The port to wait for.
+/// * `protocol` - The protocol to wait for (TCP or UDP).
+/// * `timeout` - The maximum time to wait.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve listeners for the current platform,
+/// or if the timeout expires first (naming the processes still holding the port, if any).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use listeners::Protocol;
+///
+/// if listeners::wait_for_port_free(8000, Protocol::TCP, Duration::from_secs(5)).is_ok() {
+///     println!("Port 8000 is free");
+/// }
+/// ```
+pub fn wait_for_port_free(port: u16, protocol: Protocol, timeout: Duration) -> crate::Result<()> {
+    let deadline = Instant::now() + timeout;
+    let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port);
+    // the standard library sets `SO_REUSEADDR` for TCP listeners on Unix platforms
+    let options = BindOptions {
+        reuse_addr: protocol == Protocol::TCP && cfg!(unix),
+        ..BindOptions::default()
+    };
+
+    loop {
+        let check = check_bind_with(addr, protocol, options)?;
+        if !check.would_fail() {
+            return Ok(());
+        }
+
+        if !sleep_until_next_poll(deadline) {
+            let mut holders: Vec<String> = check
+                .conflicts
+                .iter()
+                .map(|l| format!("PID {} ({})", l.process.pid, l.process.name))
+                .collect();
+            holders.sort();
+            holders.dedup();
+            if check.time_wait_conflicts > 0 {
+                holders.push(format!(
+                    "{} socket(s) in TIME_WAIT state",
+                    check.time_wait_conflicts
+                ));
+            }
+            return Err(format!(
+                "Timed out waiting for {protocol} port {port} to be free, it's held by {}",
+                holders.join(", ")
+            )
+            .into());
+        }
+    }
+}
+
+/// Sleeps until the next poll, returning `false` if the deadline has already passed.
+fn sleep_until_next_poll(deadline: Instant) -> bool {
+    let now = Instant::now();
+    if now >= deadline {
+        return false;
+    }
+    std::thread::sleep(POLL_INTERVAL.min(deadline - now));
+    true
+}
+
+/// Whether `pid` is `ancestor` or one of its descendants.
+fn is_same_or_descendant(pid: u32, ancestor: u32) -> bool {
+    let mut current = pid;
+    for _ in 0..MAX_ANCESTORS {
+        if current == ancestor {
+            return true;
+        }
+        match platform::parent_pid(current) {
+            Some(parent) if parent != current && parent != 0 => current = parent,
+            _ => return false,
+        }
+    }
+    false
+}
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::platform::Poller;
+use crate::{Listener, Snapshot, SnapshotDiff, StateChange};
+
+/// A change in the listeners, as reported by [`watch`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum ListenerEvent {
+    /// A socket was opened.
+    Opened(Listener),
+    /// A socket was closed.
+    Closed(Listener),
+    /// A socket changed its state.
+    StateChanged(StateChange),
+}
+
+/// Iterator over the [`ListenerEvent`]s, returned by [`watch`].
+///
+/// Each call to `next` blocks until at least one change is detected.
+pub struct Watcher {
+    poller: Poller,
+    snapshot: Snapshot,
+    interval: Duration,
+    next_poll: Instant,
+    events: VecDeque<ListenerEvent>,
+}
+
+/// Watches the listeners, polling them at the given interval.
+///
+/// The returned [`Watcher`] is an iterator yielding a [`ListenerEvent`] for every socket
+/// opened, closed, or changing its state since the call to this function.
+/// Sockets are identified as described in [`Snapshot::diff`].
+///
+/// On Linux, the mapping between sockets and processes is carried across polls,
+/// so that only the processes that may own a new socket are inspected.
+///
+/// # Arguments
+///
+/// * `interval` - The time between two consecutive polls.
+///
+/// # Errors
+///
+/// This function returns an error if it fails to retrieve the initial listeners for the current platform.
+///
+/// # Example
+///
+///  ``` no_run
+#[doc = include_str!("../examples/watch.rs")]
+/// ```
+///
+/// Output:
+/// ``` text
+/// Port 3000 is now taken by node (PID 4242)
+/// Port 3000 is now free
+/// ```
+pub fn watch(interval: Duration) -> crate::Result<Watcher> {
+    let mut poller = Poller::new();
+    let snapshot = Snapshot::new(poller.poll()?);
+    Ok(Watcher {
+        poller,
+        snapshot,
+        interval,
+        next_poll: Instant::now() + interval,
+        events: VecDeque::new(),
+    })
+}
+
+impl Watcher {
+    /// Returns the snapshot taken at the latest poll.
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    fn poll(&mut self) -> crate::Result<()> {
+        let now = Instant::now();
+        if self.next_poll > now {
+            std::thread::sleep(self.next_poll - now);
+        }
+        self.next_poll = Instant::now() + self.interval;
+
+        let snapshot = Snapshot::new(self.poller.poll()?);
+        let diff = self.snapshot.diff(&snapshot);
+        self.events.extend(ListenerEvent::from_diff(diff));
+        self.snapshot = snapshot;
+        Ok(())
+    }
+}
+
+impl Iterator for Watcher {
+    type Item = crate::Result<ListenerEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.events.is_empty() {
+            if let Err(e) = self.poll() {
+                return Some(Err(e));
+            }
+        }
+        self.events.pop_front().map(Ok)
+    }
+}
+
+impl ListenerEvent {
+    /// Converts a [`SnapshotDiff`] into events: closed sockets first, then state changes, then opened sockets.
+    pub fn from_diff(diff: SnapshotDiff) -> impl Iterator<Item = ListenerEvent> {
+        let SnapshotDiff {
+            opened,
+            closed,
+            state_changed,
+        } = diff;
+        closed
+            .into_iter()
+            .map(ListenerEvent::Closed)
+            .chain(state_changed.into_iter().map(ListenerEvent::StateChanged))
+            .chain(opened.into_iter().map(ListenerEvent::Opened))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::watch::ListenerEvent;
+    use crate::{Listener, Protocol, Snapshot, SocketState};
+
+    #[test]
+    fn test_events_from_diff() {
+        let opened = Listener::fixture(1, "0.0.0.0:3000", Protocol::TCP, SocketState::Listen);
+        let closed = Listener::fixture(2, "0.0.0.0:8080", Protocol::TCP, SocketState::Listen);
+        let before = Listener::fixture(
+            3,
+            "127.0.0.1:40000",
+            Protocol::TCP,
+            SocketState::Established,
+        );
+        let after = Listener::fixture(3, "127.0.0.1:40000", Protocol::TCP, SocketState::CloseWait);
+
+        let older = Snapshot::new(HashSet::from([closed.clone(), before.clone()]));
+        let newer = Snapshot::new(HashSet::from([opened.clone(), after.clone()]));
+
+        let events: Vec<ListenerEvent> = ListenerEvent::from_diff(older.diff(&newer)).collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], ListenerEvent::Closed(closed));
+        let ListenerEvent::StateChanged(change) = &events[1] else {
+            panic!("expected a state change, got {:?}", events[1]);
+        };
+        assert_eq!(change.before, before);
+        assert_eq!(change.after, after);
+        assert_eq!(events[2], ListenerEvent::Opened(opened));
+    }
+}
@@ -1,10 +1,18 @@
 use http_test_server::TestServer;
-use listeners::{Listener, Process, Protocol, SocketState, get_process_by_port};
+use listeners::{
+    AddrInUseError, AddressFamily, BindOptions, ConnectionGraph, Direction, Listener,
+    ListenerEvent, ListenerQuery, ListeningService, Process, Protocol, Snapshot, SocketLeakGuard,
+    SocketState, bind_tcp, bind_udp, check_bind, check_bind_with, get_listeners_by_pid,
+    get_listeners_by_pids, get_listeners_by_process, get_own_listeners, get_process_by_port,
+    get_process_by_socket, get_processes_by_ports, predict_receiver, wait_for_listener,
+    wait_for_port_free,
+};
 use rand::prelude::IteratorRandom;
 use serial_test::serial;
 use std::collections::HashSet;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, UdpSocket};
 use std::str::FromStr;
+use std::time::Duration;
 
 #[cfg(not(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
 #[test]
@@ -74,8 +82,11 @@ fn test_http_server() {
         listeners::get_process_by_port(http_server_port, Protocol::TCP).unwrap();
 
     let http_server_name = http_server_process.name.clone();
+    let http_server_comm = http_server_process.comm.clone();
     let http_server_pid = http_server_process.pid;
     let http_server_path = http_server_process.path.clone();
+    let http_server_exe = http_server_process.exe.clone();
+    let http_server_start_time = http_server_process.start_time;
 
     // assert that the http server process name and path are not empty
     assert!(!http_server_name.is_empty());
@@ -95,9 +106,13 @@ fn test_http_server() {
             process: Process {
                 pid: http_server_pid,
                 name: http_server_name,
-                path: http_server_path
+                comm: http_server_comm,
+                path: http_server_path,
+                exe: http_server_exe,
+                start_time: http_server_start_time,
             },
             socket: SocketAddr::from_str(&format!("127.0.0.1:{http_server_port}")).unwrap(),
+            remote: None,
             protocol: Protocol::TCP,
             state: SocketState::Listen,
         }
@@ -357,7 +372,7 @@ fn test_tcp_close_wait_state() {
 
 #[test]
 #[serial]
-fn test_udp_state_is_unknown() {
+fn test_udp_unconnected_state() {
     let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
     let socket = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
     let port = socket.local_addr().unwrap().port();
@@ -367,5 +382,820 @@ fn test_udp_state_is_unknown() {
         .iter()
         .find(|l| l.socket.port() == port && l.protocol == Protocol::UDP)
         .unwrap();
+    // the Windows UDP table doesn't expose the remote peer
+    #[cfg(target_os = "windows")]
     assert_eq!(listener.state, SocketState::Unknown);
+    #[cfg(not(target_os = "windows"))]
+    assert_eq!(listener.state, SocketState::Unconnected);
+}
+
+#[test]
+#[serial]
+fn test_udp_connected_state() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    let client = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    client.connect(server.local_addr().unwrap()).unwrap();
+    let port = client.local_addr().unwrap().port();
+
+    let all = listeners::get_all().unwrap();
+    let listener = all
+        .iter()
+        .find(|l| l.socket.port() == port && l.protocol == Protocol::UDP)
+        .unwrap();
+    #[cfg(target_os = "windows")]
+    assert_eq!(listener.state, SocketState::Unknown);
+    #[cfg(not(target_os = "windows"))]
+    assert_eq!(listener.state, SocketState::Connected);
+}
+
+#[test]
+#[serial]
+fn test_tcp_directions() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let server_addr = server.local_addr().unwrap();
+
+    let client = std::net::TcpStream::connect(server_addr).unwrap();
+    let client_addr = client.local_addr().unwrap();
+    let (_accepted, _) = server.accept().unwrap();
+
+    let all = listeners::get_all().unwrap();
+    let directions = Direction::classify(&all);
+    let direction_of = |socket: SocketAddr, state: SocketState| {
+        directions
+            .iter()
+            .find(|(l, _)| l.socket == socket && l.state == state && l.protocol == Protocol::TCP)
+            .map(|(_, d)| *d)
+            .unwrap()
+    };
+
+    assert_eq!(
+        direction_of(server_addr, SocketState::Listen),
+        Direction::Listening
+    );
+    assert_eq!(
+        direction_of(server_addr, SocketState::Established),
+        Direction::Inbound
+    );
+    assert_eq!(
+        direction_of(client_addr, SocketState::Established),
+        Direction::Outbound
+    );
+}
+
+#[test]
+#[serial]
+fn test_listening_service_connections() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let server_addr = server.local_addr().unwrap();
+
+    let mut clients = Vec::new();
+    let mut accepted = Vec::new();
+    for _ in 0..3 {
+        clients.push(std::net::TcpStream::connect(server_addr).unwrap());
+        accepted.push(server.accept().unwrap().0);
+    }
+    let client_addrs = clients
+        .iter()
+        .map(|c| c.local_addr().unwrap())
+        .collect::<Vec<_>>();
+
+    let all = listeners::get_all().unwrap();
+    let services = ListeningService::group(&all);
+    let service = services
+        .iter()
+        .find(|s| s.listener.socket == server_addr)
+        .unwrap();
+
+    assert_eq!(service.listener.remote, None);
+    assert_eq!(service.connections.len(), 3);
+    for connection in &service.connections {
+        assert_eq!(connection.socket, server_addr);
+        assert!(client_addrs.contains(&connection.remote.unwrap()));
+    }
+    assert_eq!(service.state_counts()[&SocketState::Established], 3);
+}
+
+#[test]
+#[serial]
+fn test_connection_graph() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let port = server.local_addr().unwrap().port();
+
+    let _client = std::net::TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    let (_accepted, _) = server.accept().unwrap();
+
+    let all = listeners::get_all().unwrap();
+    let graph = ConnectionGraph::build(&all);
+    let pid = std::process::id();
+    let edge = graph
+        .edges
+        .iter()
+        .find(|e| e.port == port && e.server.pid == pid)
+        .unwrap();
+    assert_eq!(edge.client.pid, pid);
+    assert_eq!(edge.connections, 1);
+    assert!(graph.to_dot().contains(&format!("\"{pid}\" -> \"{pid}\"")));
+}
+
+#[test]
+#[serial]
+fn test_snapshot_diff() {
+    let before = Snapshot::take().unwrap();
+
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let socket = server.local_addr().unwrap();
+    let pid = std::process::id();
+
+    let after = Snapshot::take().unwrap();
+    assert!(after.taken_at >= before.taken_at);
+    let diff = before.diff(&after);
+    assert!(
+        diff.opened
+            .iter()
+            .any(|l| l.socket == socket && l.process.pid == pid)
+    );
+
+    drop(server);
+    let closed = Snapshot::take().unwrap();
+    let diff = after.diff(&closed);
+    assert!(
+        diff.closed
+            .iter()
+            .any(|l| l.socket == socket && l.process.pid == pid)
+    );
+}
+
+#[test]
+#[serial]
+fn test_watch() {
+    let mut watcher = listeners::watch(std::time::Duration::from_millis(50)).unwrap();
+
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let socket = server.local_addr().unwrap();
+    let pid = std::process::id();
+
+    let opened = watcher
+        .by_ref()
+        .flatten()
+        .find_map(|e| match e {
+            ListenerEvent::Opened(l) if l.socket == socket => Some(l),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(opened.process.pid, pid);
+    assert_eq!(opened.state, SocketState::Listen);
+    assert!(watcher.snapshot().listeners.contains(&opened));
+
+    drop(server);
+    let closed = watcher
+        .flatten()
+        .find_map(|e| match e {
+            ListenerEvent::Closed(l) if l.socket == socket => Some(l),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(closed, opened);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+#[serial]
+async fn test_tokio_get_all_and_get_process_by_port() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let socket = server.local_addr().unwrap();
+
+    let all = listeners::tokio::get_all().await.unwrap();
+    assert!(all.iter().any(|l| l.socket == socket));
+
+    let process = listeners::tokio::get_process_by_port(socket.port(), Protocol::TCP)
+        .await
+        .unwrap();
+    assert_eq!(process.pid, std::process::id());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+#[serial]
+async fn test_tokio_watch() {
+    use futures_util::StreamExt;
+
+    let stream = listeners::tokio::watch(std::time::Duration::from_millis(50))
+        .await
+        .unwrap();
+    let mut stream = std::pin::pin!(stream);
+
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let socket = server.local_addr().unwrap();
+
+    while let Some(event) = stream.next().await {
+        if let Ok(ListenerEvent::Opened(l)) = event
+            && l.socket == socket
+        {
+            assert_eq!(l.process.pid, std::process::id());
+            return;
+        }
+    }
+    panic!("the stream ended without reporting the new socket");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+#[serial]
+fn test_cli() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let server = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let socket = server.local_addr().unwrap();
+    let port = socket.port().to_string();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_listeners"))
+        .args([
+            "--port", &port, "--tcp", "--state", "listen", "--output", "csv",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("pid,name,path,protocol,socket,remote,state")
+    );
+    let row = lines.next().unwrap();
+    assert!(row.starts_with(&format!("{},", std::process::id())));
+    assert!(row.ends_with(&format!(",TCP,{socket},,LISTEN")));
+    assert_eq!(lines.next(), None);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_listeners"))
+        .args(["--port", "not-a-port"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+#[serial]
+fn test_listener_query() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    let tcp_socket = tcp.local_addr().unwrap();
+    let udp_socket = udp.local_addr().unwrap();
+    let pid = std::process::id();
+
+    let query = ListenerQuery::new()
+        .protocol(Protocol::TCP)
+        .family(AddressFamily::IPv4)
+        .state(SocketState::Listen)
+        .pid(pid);
+    let listeners = query.execute().unwrap();
+    assert!(listeners.iter().all(|l| query.matches(l)));
+    assert!(listeners.iter().any(|l| l.socket == tcp_socket));
+    assert!(listeners.iter().all(|l| l.socket != udp_socket));
+
+    let by_port = ListenerQuery::new()
+        .protocol(Protocol::UDP)
+        .port(udp_socket.port())
+        .local_addr(ip)
+        .execute()
+        .unwrap();
+    assert_eq!(by_port.len(), 1);
+    let l = by_port.iter().next().unwrap();
+    assert_eq!(l.socket, udp_socket);
+    assert_eq!(l.process.pid, pid);
+
+    // the query returns the same listeners as filtering all of them
+    let query = ListenerQuery::new().protocol(Protocol::TCP);
+    let expected: HashSet<Listener> = listeners::get_all()
+        .unwrap()
+        .into_iter()
+        .filter(|l| query.matches(l) && l.process.pid == pid)
+        .collect();
+    let actual: HashSet<Listener> = query.pid(pid).execute().unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[serial]
+fn test_get_listeners_by_pid() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    let pid = std::process::id();
+
+    let expected: HashSet<Listener> = listeners::get_all()
+        .unwrap()
+        .into_iter()
+        .filter(|l| l.process.pid == pid)
+        .collect();
+    let listeners = get_listeners_by_pid(pid).unwrap();
+    assert_eq!(listeners, expected);
+    assert!(
+        listeners
+            .iter()
+            .any(|l| l.socket == tcp.local_addr().unwrap())
+    );
+    assert!(
+        listeners
+            .iter()
+            .any(|l| l.socket == udp.local_addr().unwrap())
+    );
+
+    // PIDs not owning any socket, or not existing at all
+    assert_eq!(get_listeners_by_pids(&[pid, u32::MAX]).unwrap(), expected);
+    assert!(get_listeners_by_pid(u32::MAX).unwrap().is_empty());
+    assert!(get_listeners_by_pids(&[]).unwrap().is_empty());
+}
+
+#[test]
+#[serial]
+fn test_get_listeners_by_process() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let pid = std::process::id();
+
+    let own = get_listeners_by_pid(pid).unwrap();
+    let process = own
+        .iter()
+        .find(|l| l.socket == tcp.local_addr().unwrap())
+        .unwrap()
+        .process
+        .clone();
+
+    let by_name = get_listeners_by_process(&process.name).unwrap();
+    assert!(by_name.is_superset(&own));
+    assert!(by_name.iter().all(|l| l.process.name == process.name));
+
+    let by_glob = get_listeners_by_process(&format!("{}*", &process.name[..1])).unwrap();
+    assert!(by_glob.is_superset(&own));
+
+    if !process.path.is_empty() {
+        let by_path = get_listeners_by_process(&process.path).unwrap();
+        assert!(by_path.is_superset(&own));
+        assert!(by_path.iter().all(|l| l.process.path == process.path));
+    }
+
+    assert!(
+        get_listeners_by_process("no-such-process-name")
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+#[serial]
+fn test_get_processes_by_ports() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    let tcp_port = tcp.local_addr().unwrap().port();
+    let udp_port = udp.local_addr().unwrap().port();
+    // a port that's no longer bound
+    let free_port = TcpListener::bind(SocketAddr::new(ip, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let processes = get_processes_by_ports(&[
+        (tcp_port, Protocol::TCP),
+        (udp_port, Protocol::UDP),
+        (free_port, Protocol::TCP),
+        (0, Protocol::TCP),
+    ])
+    .unwrap();
+
+    assert_eq!(processes.len(), 2);
+    for key in [(tcp_port, Protocol::TCP), (udp_port, Protocol::UDP)] {
+        let process = &processes[&key];
+        assert_eq!(process.pid, std::process::id());
+        assert_eq!(process, &get_process_by_port(key.0, key.1).unwrap());
+    }
+
+    assert!(get_processes_by_ports(&[]).unwrap().is_empty());
+}
+
+#[test]
+#[serial]
+fn test_get_process_by_socket() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let tcp_addr = tcp.local_addr().unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).unwrap();
+    let udp_port = udp.local_addr().unwrap().port();
+    let pid = std::process::id();
+
+    // exact address
+    let process = get_process_by_socket(tcp_addr, Protocol::TCP).unwrap();
+    assert_eq!(process.pid, pid);
+
+    // same port on another address
+    let other = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), tcp_addr.port());
+    assert!(get_process_by_socket(other, Protocol::TCP).is_err());
+
+    // wildcard listener receiving traffic for the address
+    let process = get_process_by_socket(SocketAddr::new(ip, udp_port), Protocol::UDP).unwrap();
+    assert_eq!(process.pid, pid);
+
+    assert!(get_process_by_socket(SocketAddr::new(ip, 0), Protocol::TCP).is_err());
+}
+
+#[test]
+#[serial]
+fn test_predict_receiver() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let tcp_addr = tcp.local_addr().unwrap();
+
+    let receiver = predict_receiver(tcp_addr, Protocol::TCP).unwrap().unwrap();
+    assert!(receiver.exact);
+    assert_eq!(receiver.listeners.len(), 1);
+    assert_eq!(receiver.listeners[0].socket, tcp_addr);
+    assert_eq!(receiver.listeners[0].process.pid, std::process::id());
+
+    drop(tcp);
+    assert!(predict_receiver(tcp_addr, Protocol::TCP).unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn test_check_bind() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let tcp_addr = tcp.local_addr().unwrap();
+
+    let check = check_bind(tcp_addr, Protocol::TCP).unwrap();
+    assert!(check.would_fail());
+    assert!(
+        check
+            .conflicts
+            .iter()
+            .any(|l| l.socket == tcp_addr && l.process.pid == std::process::id())
+    );
+    assert!(TcpListener::bind(tcp_addr).is_err());
+
+    // the listening socket conflicts even with SO_REUSEADDR
+    let reuse_addr = BindOptions {
+        reuse_addr: true,
+        ..BindOptions::default()
+    };
+    assert!(
+        check_bind_with(tcp_addr, Protocol::TCP, reuse_addr)
+            .unwrap()
+            .would_fail()
+    );
+
+    // other addresses of the same port are free
+    let other = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), tcp_addr.port());
+    assert!(!check_bind(other, Protocol::TCP).unwrap().would_fail());
+
+    drop(tcp);
+    assert!(!check_bind(tcp_addr, Protocol::TCP).unwrap().would_fail());
+    assert!(
+        !check_bind(SocketAddr::new(ip, 0), Protocol::TCP)
+            .unwrap()
+            .would_fail()
+    );
+}
+
+#[test]
+#[serial]
+fn test_bind_addr_in_use() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = bind_tcp(SocketAddr::new(ip, 0)).unwrap();
+    let tcp_addr = tcp.local_addr().unwrap();
+    let udp = bind_udp(SocketAddr::new(ip, 0)).unwrap();
+    let udp_addr = udp.local_addr().unwrap();
+
+    for (error, addr, protocol) in [
+        (bind_tcp(tcp_addr).unwrap_err(), tcp_addr, Protocol::TCP),
+        (bind_udp(udp_addr).unwrap_err(), udp_addr, Protocol::UDP),
+    ] {
+        assert_eq!(error.kind(), std::io::ErrorKind::AddrInUse);
+        let error = error
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<AddrInUseError>())
+            .unwrap();
+        assert_eq!(error.addr, addr);
+        assert_eq!(error.protocol, protocol);
+        assert!(error.processes.iter().any(|p| p.pid == std::process::id()));
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("PID {}", std::process::id()))
+        );
+    }
+
+    // other errors are returned unchanged
+    assert_eq!(
+        bind_tcp(&[][..]).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+#[serial]
+fn test_wait_for_listener() {
+    let port = TcpListener::bind("0.0.0.0:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_spawn_process"))
+        .args(["1", &port.to_string()])
+        .spawn()
+        .unwrap();
+    let timeout = Duration::from_secs(10);
+
+    let listener = wait_for_listener(port, Protocol::TCP, Some(child.id()), timeout).unwrap();
+    assert_eq!(listener.process.pid, child.id());
+    assert_eq!(listener.socket.port(), port);
+    assert_eq!(listener.state, SocketState::Listen);
+    assert_eq!(
+        wait_for_listener(port, Protocol::TCP, None, timeout).unwrap(),
+        listener
+    );
+    #[cfg(target_os = "linux")]
+    assert_eq!(
+        wait_for_listener(port, Protocol::TCP, Some(std::process::id()), timeout).unwrap(),
+        listener
+    );
+
+    // the port is held by another process
+    let error = wait_for_listener(port, Protocol::TCP, Some(u32::MAX), Duration::ZERO)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains(&format!("held by PID {}", child.id())));
+    assert!(wait_for_port_free(port, Protocol::TCP, Duration::from_millis(50)).is_err());
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    wait_for_port_free(port, Protocol::TCP, timeout).unwrap();
+    assert!(wait_for_listener(port, Protocol::TCP, None, Duration::from_millis(50)).is_err());
+}
+
+#[test]
+#[serial]
+fn test_socket_leak_guard() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let guard = SocketLeakGuard::new().unwrap();
+    assert!(guard.leaks().unwrap().is_empty());
+
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    let leaks = guard.leaks().unwrap();
+    let leaked: HashSet<(SocketAddr, Protocol)> = leaks
+        .listeners
+        .iter()
+        .map(|l| (l.socket, l.protocol))
+        .collect();
+    assert!(leaked.contains(&(tcp.local_addr().unwrap(), Protocol::TCP)));
+    assert!(leaked.contains(&(udp.local_addr().unwrap(), Protocol::UDP)));
+    let error = guard.check().unwrap_err().to_string();
+    assert!(error.contains(&format!("TCP {} LISTEN", tcp.local_addr().unwrap())));
+
+    // dropping a guard with leaks panics
+    let result = std::panic::catch_unwind(|| drop(SocketLeakGuard::new().unwrap()));
+    assert!(result.is_ok());
+    let result = std::panic::catch_unwind(|| {
+        let guard = SocketLeakGuard::new().unwrap();
+        let _leaked = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+        drop(guard);
+    });
+    assert!(result.is_err());
+    let result = std::panic::catch_unwind(|| {
+        let guard = SocketLeakGuard::new().unwrap().without_panic();
+        let _leaked = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+        assert!(guard.check().is_err());
+        drop(guard);
+    });
+    assert!(result.is_ok());
+
+    // sockets opened before a reset aren't reported
+    let mut reset_guard = SocketLeakGuard::new().unwrap();
+    let before_reset = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    reset_guard.reset().unwrap();
+    reset_guard.check().unwrap();
+    drop(before_reset);
+    reset_guard.check().unwrap();
+
+    drop(tcp);
+    drop(udp);
+    guard.check().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_socket_leak_guard_with_children() {
+    let port = TcpListener::bind("0.0.0.0:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let guard = SocketLeakGuard::with_children().unwrap();
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_spawn_process"))
+        .args(["1", &port.to_string()])
+        .spawn()
+        .unwrap();
+    wait_for_listener(
+        port,
+        Protocol::TCP,
+        Some(child.id()),
+        Duration::from_secs(10),
+    )
+    .unwrap();
+
+    // children are only tracked on Linux
+    let child_leaked = guard
+        .leaks()
+        .unwrap()
+        .listeners
+        .iter()
+        .any(|l| l.process.pid == child.id() && l.socket.port() == port);
+    assert_eq!(child_leaked, cfg!(target_os = "linux"));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    guard.check().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_get_own_listeners() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+
+    let own = get_own_listeners().unwrap();
+    assert_eq!(own, get_listeners_by_pid(std::process::id()).unwrap());
+    assert!(own.iter().any(|l| l.socket == tcp.local_addr().unwrap()));
+    assert!(own.iter().any(|l| l.socket == udp.local_addr().unwrap()));
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_describe_fd() {
+    use std::os::fd::AsFd;
+
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let udp = UdpSocket::bind(SocketAddr::new(ip, 0)).unwrap();
+    let client = std::net::TcpStream::connect(tcp.local_addr().unwrap()).unwrap();
+
+    let l = listeners::describe_fd(tcp.as_fd()).unwrap();
+    assert_eq!(l.socket, tcp.local_addr().unwrap());
+    assert_eq!(l.protocol, Protocol::TCP);
+    assert_eq!(l.state, SocketState::Listen);
+    assert_eq!(l.process.pid, std::process::id());
+
+    let l = listeners::describe_fd(udp.as_fd()).unwrap();
+    assert_eq!(l.socket, udp.local_addr().unwrap());
+    assert_eq!(l.protocol, Protocol::UDP);
+
+    // TCP and UDP sockets bound to the same address are told apart
+    let udp_same_addr = UdpSocket::bind(tcp.local_addr().unwrap()).unwrap();
+    let l = listeners::describe_fd(udp_same_addr.as_fd()).unwrap();
+    assert_eq!(l.socket, tcp.local_addr().unwrap());
+    assert_eq!(l.protocol, Protocol::UDP);
+
+    let l = listeners::describe_fd(client.as_fd()).unwrap();
+    assert_eq!(l.socket, client.local_addr().unwrap());
+    assert_eq!(l.remote, Some(tcp.local_addr().unwrap()));
+    assert_eq!(l.state, SocketState::Established);
+
+    let file = std::fs::File::open("Cargo.toml").unwrap();
+    assert!(listeners::describe_fd(file.as_fd()).is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial]
+fn test_process_info() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let process = get_process_by_port(tcp.local_addr().unwrap().port(), Protocol::TCP).unwrap();
+
+    let info = process.info().unwrap();
+    let args: Vec<std::ffi::OsString> = std::env::args_os().collect();
+    assert_eq!(info.cmdline, args);
+    assert_eq!(info.parent_pid, Some(std::os::unix::process::parent_id()));
+    assert!(info.uid.is_some() && info.effective_uid.is_some());
+    assert!(info.gid.is_some() && info.effective_gid.is_some());
+    assert_eq!(info.cwd, Some(std::env::current_dir().unwrap()));
+    assert!(info.session.is_some());
+    let start_time = info.start_time.unwrap();
+    let now = std::time::SystemTime::now();
+    assert!(start_time <= now);
+    assert!(now.duration_since(start_time).unwrap() < Duration::from_secs(3600));
+
+    // a process with the same PID started at another time isn't described
+    let reused = Process {
+        start_time: Some(std::time::SystemTime::UNIX_EPOCH),
+        ..process.clone()
+    };
+    assert!(reused.info().is_err());
+
+    let exited = Process {
+        pid: u32::MAX,
+        ..process
+    };
+    assert!(exited.info().is_err());
+}
+
+#[test]
+#[serial]
+fn test_process_identity() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let process = get_process_by_port(tcp.local_addr().unwrap().port(), Protocol::TCP).unwrap();
+
+    #[cfg(target_os = "linux")]
+    assert_eq!(process.start_time, process.info().unwrap().start_time);
+    #[cfg(target_os = "linux")]
+    assert!(process.start_time.is_some());
+
+    // the identity is stable across calls
+    assert!(
+        listeners::get_all()
+            .unwrap()
+            .iter()
+            .any(|l| l.process == process)
+    );
+
+    // a process with the same PID started at another time is a different process
+    let reused = Process {
+        start_time: Some(std::time::SystemTime::UNIX_EPOCH),
+        ..process.clone()
+    };
+    assert_ne!(reused, process);
+}
+
+#[test]
+#[serial]
+fn test_process_exe() {
+    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let tcp = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let process = get_process_by_port(tcp.local_addr().unwrap().port(), Protocol::TCP).unwrap();
+
+    #[cfg(not(target_os = "openbsd"))]
+    {
+        let exe = std::env::current_exe().unwrap();
+        assert_eq!(process.path, exe.to_string_lossy());
+        assert_eq!(process.exe, Ok(exe));
+    }
+    #[cfg(target_os = "openbsd")]
+    {
+        assert!(process.path.is_empty());
+        assert_eq!(process.exe, Err(listeners::PathUnavailable::Unsupported));
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial]
+fn test_process_exe_not_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::process::CommandExt;
+
+    let dir = std::env::temp_dir().join(format!("listeners-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let exe = dir.join(OsStr::from_bytes(b"spawn_\xffprocess"));
+    std::fs::copy(env!("CARGO_BIN_EXE_spawn_process"), &exe).unwrap();
+
+    let port = TcpListener::bind("0.0.0.0:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    // the program reads its arguments with `std::env::args`, which only supports UTF-8
+    let mut child = std::process::Command::new(&exe)
+        .arg0("spawn_process")
+        .args(["1", &port.to_string()])
+        .spawn()
+        .unwrap();
+    let listener = wait_for_listener(
+        port,
+        Protocol::TCP,
+        Some(child.id()),
+        Duration::from_secs(10),
+    )
+    .unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // the path is only lossy as a string
+    assert_eq!(listener.process.exe, Ok(exe.clone()));
+    assert_eq!(listener.process.path, exe.to_string_lossy());
+    assert!(listener.process.path.contains(char::REPLACEMENT_CHARACTER));
+    assert_eq!(listener.process.name, "spawn_\u{fffd}process");
 }